@@ -0,0 +1,239 @@
+//! Async command execution backed by `async-process`, so a timeout no longer costs an
+//! OS thread plus an `mpsc` channel per invocation (as the waiter thread in
+//! `executor::run_command_structured` still does). Timeout becomes a race between the child's exit and a
+//! timer via `futures_lite::future::or`; child-exit reaping is handled internally by
+//! `async-process` (backed by `signal-hook`'s SIGCHLD handling) instead of polling.
+//!
+//! `run_command` stays the public, synchronous entry point - it's a thin `block_on`
+//! wrapper around `run_command_async` here, so existing callers see no difference.
+
+use std::process::{Command, ExitStatus};
+use std::time::Duration;
+
+use async_io::Timer;
+use async_process::{Child, Command as AsyncCommand, Stdio as AsyncStdio};
+use futures_lite::future;
+use futures_lite::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::executor::{kill_process_group, terminate_process_group, ExecutionResult};
+use crate::request::ExecutionContext;
+
+/// Run `cmd` under `ctx`, using `async-process` instead of a dedicated waiter thread.
+pub async fn run_command_async(cmd: Command, ctx: &ExecutionContext) -> ExecutionResult {
+    let mut acmd = to_async_command(&cmd);
+    if let Some(dir) = &ctx.working_dir {
+        acmd.current_dir(dir);
+    }
+    if let Some(env) = &ctx.env {
+        for (key, value) in env {
+            acmd.env(key, value);
+        }
+    }
+    if ctx.stdin.is_some() {
+        acmd.stdin(AsyncStdio::piped());
+    }
+    acmd.stdout(AsyncStdio::piped());
+    acmd.stderr(AsyncStdio::piped());
+    isolate_process_group_async(&mut acmd);
+
+    let mut child = match acmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return ExecutionResult::Error(format!("Failed to spawn command: {}", e)),
+    };
+    let pid = child.id();
+    let stdin_data = ctx.stdin.clone();
+    let stdin_handle = child.stdin.take();
+
+    let wait_and_read = async {
+        let status = match ctx.timeout {
+            None => Some(child.status().await),
+            Some(timeout) => race_with_timeout(child.status(), timeout).await,
+        };
+
+        let status = match status {
+            Some(result) => result,
+            None => {
+                // Timed out waiting for exit.
+                let Some(grace) = ctx.kill_grace else {
+                    kill_process_group(pid);
+                    return ExecutionResult::Timeout;
+                };
+
+                terminate_process_group(pid);
+                match race_with_timeout(child.status(), grace).await {
+                    Some(Ok(_status)) => {
+                        let (stdout, _stderr) = read_output(&mut child).await;
+                        return ExecutionResult::TimeoutGraceful(stdout);
+                    }
+                    Some(Err(e)) => return ExecutionResult::Error(format!("Command failed: {}", e)),
+                    None => {
+                        kill_process_group(pid);
+                        return ExecutionResult::Timeout;
+                    }
+                }
+            }
+        };
+
+        match status {
+            Ok(status) => {
+                let (stdout, stderr) = read_output(&mut child).await;
+                if status.success() {
+                    ExecutionResult::Success(stdout)
+                } else if stderr.is_empty() {
+                    ExecutionResult::Error(format!("Error: {}", stdout))
+                } else {
+                    ExecutionResult::Error(format!("Error: {}", stderr))
+                }
+            }
+            Err(e) => ExecutionResult::Error(format!("Command failed: {}", e)),
+        }
+    };
+
+    let feed_stdin = async {
+        if let (Some(mut pipe), Some(data)) = (stdin_handle, stdin_data) {
+            let _ = pipe.write_all(data.as_bytes()).await;
+            // `pipe` drops here, closing the fd and signaling EOF to the child.
+        }
+    };
+
+    // Run concurrently rather than sequentially: a command that doesn't read stdin until
+    // it has produced some output would otherwise deadlock if we waited for the write to
+    // finish before polling for exit.
+    future::zip(feed_stdin, wait_and_read).await.1
+}
+
+/// Drain whatever remains of the child's stdout/stderr pipes now that it has exited (or
+/// been killed). Lossy-UTF8 decoded to match `ExecutionResult`'s string-based variants.
+async fn read_output(child: &mut Child) -> (String, String) {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    if let Some(out) = child.stdout.as_mut() {
+        let _ = out.read_to_end(&mut stdout).await;
+    }
+    if let Some(err) = child.stderr.as_mut() {
+        let _ = err.read_to_end(&mut stderr).await;
+    }
+    (
+        String::from_utf8_lossy(&stdout).into_owned(),
+        String::from_utf8_lossy(&stderr).into_owned(),
+    )
+}
+
+/// Race a child-exit future against a timer. Returns `None` if the timer won (the child
+/// is still running), `Some(result)` if the child exited first.
+async fn race_with_timeout(
+    status: impl std::future::Future<Output = std::io::Result<ExitStatus>>,
+    timeout: Duration,
+) -> Option<std::io::Result<ExitStatus>> {
+    future::or(async { Some(status.await) }, async {
+        Timer::after(timeout).await;
+        None
+    })
+    .await
+}
+
+/// Rebuild an `async_process::Command` from a `std::process::Command`'s program, args,
+/// env vars and working directory - the pieces `std::process::Command` exposes getters
+/// for. Stdio configuration isn't carried over (no stable getter exists for it); callers
+/// configure stdio on the returned command directly, as `run_command_async` does above.
+fn to_async_command(cmd: &Command) -> AsyncCommand {
+    let mut acmd = AsyncCommand::new(cmd.get_program());
+    acmd.args(cmd.get_args());
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => acmd.env(key, value),
+            None => acmd.env_remove(key),
+        };
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        acmd.current_dir(dir);
+    }
+    acmd
+}
+
+/// Put the child into its own process group (Unix) / process group marker (Windows)
+/// before spawning - the `async-process` equivalent of `executor::isolate_process_group`,
+/// needed because `to_async_command` can't carry that setting over from the source
+/// `std::process::Command`.
+fn isolate_process_group_async(cmd: &mut AsyncCommand) {
+    #[cfg(unix)]
+    {
+        use async_process::unix::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use async_process::windows::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command_async_simple() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("hello");
+        let ctx = ExecutionContext::default();
+        let result = future::block_on(run_command_async(cmd, &ctx));
+        match result {
+            ExecutionResult::Success(s) => assert!(s.contains("hello")),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_async_timeout() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let result = future::block_on(run_command_async(cmd, &ctx));
+        assert!(matches!(result, ExecutionResult::Timeout));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_async_timeout_with_grace_exits_gracefully() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            kill_grace: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let result = future::block_on(run_command_async(cmd, &ctx));
+        assert!(matches!(result, ExecutionResult::TimeoutGraceful(_)));
+    }
+
+    #[test]
+    fn test_run_command_async_feeds_stdin_to_cat() {
+        let cmd = Command::new("cat");
+        let ctx = ExecutionContext {
+            stdin: Some("hello from async stdin".to_string()),
+            ..Default::default()
+        };
+        let result = future::block_on(run_command_async(cmd, &ctx));
+        match result {
+            ExecutionResult::Success(s) => assert_eq!(s, "hello from async stdin"),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_via_block_on_wrapper_matches_async_behavior() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("wrapped");
+        let ctx = ExecutionContext::default();
+        let result = crate::executor::run_command(cmd, &ctx);
+        match result {
+            ExecutionResult::Success(s) => assert!(s.contains("wrapped")),
+            _ => panic!("Expected success"),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::request::ExecutionContext;
+
+/// Directory (under the system temp dir) holding cached command outputs.
+const CACHE_DIR: &str = "command-runner-mcp-cache";
+
+/// Maximum number of entries kept in the on-disk store before the oldest is evicted.
+const MAX_CACHE_ENTRIES: usize = 1000;
+
+/// A per-process random hash key, so cache keys can't be predicted by another local
+/// user who knows the cache dir is shared (it lives under the system temp dir) - unlike
+/// a fixed-seed hasher, two different server processes never produce the same key for
+/// the same input.
+static CACHE_HASH_STATE: LazyLock<RandomState> = LazyLock::new(RandomState::new);
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    output: String,
+    stored_at_ms: u128,
+    ttl_ms: u64,
+}
+
+/// Build a stable cache key from the tool name, the serialized inner request, and the
+/// execution context fields that can affect the raw command output (working_dir, env).
+/// Returns `None` if the request can't be serialized, in which case the caller should
+/// skip caching rather than fail the call.
+pub fn cache_key<T: Serialize>(tool: &str, inner: &T, ctx: &ExecutionContext) -> Option<String> {
+    let inner_bytes = bincode::serialize(inner).ok()?;
+
+    let mut hasher = CACHE_HASH_STATE.build_hasher();
+    tool.hash(&mut hasher);
+    inner_bytes.hash(&mut hasher);
+    ctx.working_dir.hash(&mut hasher);
+    if let Some(env) = &ctx.env {
+        let mut pairs: Vec<_> = env.iter().collect();
+        pairs.sort();
+        pairs.hash(&mut hasher);
+    }
+
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+fn cache_path(key: &str) -> PathBuf {
+    std::env::temp_dir().join(CACHE_DIR).join(format!("{}.bin", key))
+}
+
+/// Look up a cached entry, returning its stored output if present and not expired.
+/// An expired entry is removed from disk.
+pub fn get(key: &str) -> Option<String> {
+    let dir = std::env::temp_dir().join(CACHE_DIR);
+    if !dir_owned_by_us(&dir) {
+        return None;
+    }
+
+    let path = cache_path(key);
+    let bytes = read_cache_file(&path).ok()?;
+    let entry: CacheEntry = bincode::deserialize(&bytes).ok()?;
+
+    let age_ms = now_ms().saturating_sub(entry.stored_at_ms);
+    if age_ms > entry.ttl_ms as u128 {
+        let _ = std::fs::remove_file(&path);
+        return None;
+    }
+
+    Some(entry.output)
+}
+
+/// Store `output` under `key` with the given TTL, evicting the oldest entry first if
+/// the bounded on-disk store is full.
+pub fn put(key: &str, output: &str, ttl_ms: u64) {
+    let dir = std::env::temp_dir().join(CACHE_DIR);
+    if std::fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    // `create_dir_all` is a silent no-op if another local user already created this
+    // shared, fixed-name directory under the system temp dir ahead of us - in that
+    // case `harden_dir_permissions` below would also silently fail (we don't own it),
+    // leaving the cache at whatever permissions the other user chose. Refuse to use
+    // the cache at all rather than read or write through a directory we don't own.
+    if !dir_owned_by_us(&dir) {
+        return;
+    }
+    harden_dir_permissions(&dir);
+    evict_oldest_if_full(&dir);
+
+    let entry = CacheEntry {
+        output: output.to_string(),
+        stored_at_ms: now_ms(),
+        ttl_ms,
+    };
+    if let Ok(bytes) = bincode::serialize(&entry) {
+        let _ = write_cache_file(&cache_path(key), &bytes);
+    }
+}
+
+/// Whether `dir` exists and is owned by our own effective user - the cache dir lives
+/// under the shared system temp dir at a fixed, predictable name, so another local
+/// user could pre-create it before we start up; in that case we can't chmod it (and
+/// set_permissions would silently fail), so we must refuse to use it at all rather
+/// than read or write through a directory someone else controls.
+#[cfg(unix)]
+fn dir_owned_by_us(dir: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(dir)
+        .map(|meta| meta.uid() == nix::unistd::geteuid().as_raw())
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn dir_owned_by_us(_dir: &Path) -> bool {
+    true
+}
+
+/// Restrict `dir` to owner-only access - the cache dir lives under the shared system
+/// temp dir, so without this any other local user could list or read cached command
+/// output (which may include working_dir/env values and command stdout).
+#[cfg(unix)]
+fn harden_dir_permissions(dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700));
+}
+
+#[cfg(not(unix))]
+fn harden_dir_permissions(_dir: &Path) {}
+
+/// Write `bytes` to `path` as an owner-only file, refusing to follow a pre-existing
+/// symlink at that location - without `O_NOFOLLOW`, another local user could plant a
+/// symlink at a predicted cache path ahead of time and have our write land wherever
+/// they pointed it.
+#[cfg(unix)]
+fn write_cache_file(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .custom_flags(nix::libc::O_NOFOLLOW)
+        .open(path)?;
+    file.write_all(bytes)
+}
+
+#[cfg(not(unix))]
+fn write_cache_file(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, bytes)
+}
+
+/// Read `path` back, refusing to follow a symlink planted at that location for the same
+/// reason `write_cache_file` refuses to write through one.
+#[cfg(unix)]
+fn read_cache_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(nix::libc::O_NOFOLLOW)
+        .open(path)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(not(unix))]
+fn read_cache_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    std::fs::read(path)
+}
+
+fn evict_oldest_if_full(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    if files.len() < MAX_CACHE_ENTRIES {
+        return;
+    }
+    files.sort_by_key(|e| e.metadata().and_then(|m| m.modified()).ok());
+    if let Some(oldest) = files.first() {
+        let _ = std::fs::remove_file(oldest.path());
+    }
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_cache_key_stable_for_same_inputs() {
+        let ctx = ExecutionContext {
+            working_dir: Some("/tmp".to_string()),
+            ..Default::default()
+        };
+        let a = cache_key("git", &"status".to_string(), &ctx);
+        let b = cache_key("git", &"status".to_string(), &ctx);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_cache_key_differs_by_tool() {
+        let ctx = ExecutionContext::default();
+        let a = cache_key("ls_tool", &"status".to_string(), &ctx);
+        let b = cache_key("git", &"status".to_string(), &ctx);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_cache_key_differs_by_working_dir() {
+        let ctx_a = ExecutionContext {
+            working_dir: Some("/tmp/a".to_string()),
+            ..Default::default()
+        };
+        let ctx_b = ExecutionContext {
+            working_dir: Some("/tmp/b".to_string()),
+            ..Default::default()
+        };
+        let a = cache_key("git", &"status".to_string(), &ctx_a);
+        let b = cache_key("git", &"status".to_string(), &ctx_b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_put_then_get_round_trips() {
+        let key = cache_key("git", &"round-trip-test".to_string(), &ExecutionContext::default()).unwrap();
+        put(&key, "cached output", 60_000);
+        assert_eq!(get(&key), Some("cached output".to_string()));
+    }
+
+    #[test]
+    fn test_get_returns_none_for_expired_entry() {
+        let key = cache_key("git", &"expiry-test".to_string(), &ExecutionContext::default()).unwrap();
+        put(&key, "stale output", 0);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(get(&key), None);
+    }
+
+    #[test]
+    fn test_get_returns_none_for_missing_entry() {
+        assert_eq!(get("nonexistent-key-0123456789"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_owned_by_us_true_for_own_temp_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(dir_owned_by_us(temp_dir.path()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_owned_by_us_false_for_missing_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(!dir_owned_by_us(&temp_dir.path().join("does-not-exist")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_put_writes_owner_only_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let key = cache_key("git", &"perm-test".to_string(), &ExecutionContext::default()).unwrap();
+        put(&key, "some output", 60_000);
+
+        let mode = std::fs::metadata(cache_path(&key)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_cache_key_differs_by_env() {
+        let mut env_a = HashMap::new();
+        env_a.insert("FOO".to_string(), "bar".to_string());
+        let ctx_a = ExecutionContext {
+            env: Some(env_a),
+            ..Default::default()
+        };
+        let a = cache_key("git", &"status".to_string(), &ctx_a);
+        let b = cache_key("git", &"status".to_string(), &ExecutionContext::default());
+        assert_ne!(a, b);
+    }
+}
@@ -1,114 +1,475 @@
-use std::process::{Command, Output};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
 use std::time::Duration;
 use std::thread;
 use std::sync::mpsc;
 
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
 use crate::request::ExecutionContext;
 
+/// Structured result of running a command, following the principle that a non-zero exit
+/// status is a first-class error rather than something to collapse into a single opaque
+/// string. `exit_code` is `None` when the process was killed by a signal (or never
+/// produced an exit status at all, e.g. it timed out or could not be spawned).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CommandResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+}
+
+impl CommandResult {
+    fn from_output(output: Output) -> Self {
+        Self {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code(),
+            success: output.status.success(),
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            stdout: String::new(),
+            stderr: message,
+            exit_code: None,
+            success: false,
+        }
+    }
+
+    /// Flatten to the single string the non-structured tool surface returns: stdout on
+    /// success, otherwise stderr (falling back to stdout if the process wrote nothing to
+    /// stderr). This is the compatibility path for callers that haven't opted into the
+    /// structured `CommandResult` form.
+    pub fn into_string(self) -> String {
+        if self.success {
+            self.stdout
+        } else if !self.stderr.is_empty() {
+            format!("Error: {}", self.stderr)
+        } else {
+            format!("Error: {}", self.stdout)
+        }
+    }
+}
+
+/// Resolve `program` to an absolute path by scanning `PATH` (and `PATHEXT` on Windows)
+/// and build a `Command` from that resolved path.
+///
+/// `Command::new("ls")`/`Command::new("git")` with a bare program name lets the OS
+/// resolve it, which on Windows (and in any directory an attacker can drop files into)
+/// can run an executable named `ls`/`git` from the current working directory instead of
+/// the trusted system binary. Routing every tool's command construction through here
+/// ensures only a fully-resolved, absolute-path binary is ever spawned.
+pub fn create_command(program: &str) -> Result<Command, String> {
+    resolve_executable(program)
+        .map(Command::new)
+        .ok_or_else(|| format!("Could not resolve '{}' to an executable on PATH", program))
+}
+
+fn resolve_executable(program: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    let extensions = executable_extensions();
+
+    for dir in std::env::split_paths(&path_var) {
+        for ext in &extensions {
+            let candidate = if ext.is_empty() {
+                dir.join(program)
+            } else {
+                dir.join(format!("{}.{}", program, ext))
+            };
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+fn executable_extensions() -> Vec<String> {
+    std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .map(|ext| ext.trim_start_matches('.').to_lowercase())
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn executable_extensions() -> Vec<String> {
+    vec![String::new()]
+}
+
 /// Result of command execution
 pub enum ExecutionResult {
     Success(String),
     Error(String),
+    /// The command timed out and had to be force-killed with SIGKILL, either because no
+    /// `kill_grace` was configured or because the process was still alive after it
+    /// elapsed.
     Timeout,
+    /// The command timed out but exited on its own after being sent SIGTERM, within the
+    /// configured `kill_grace` window, instead of needing to be force-killed. Carries
+    /// whatever stdout it produced before exiting.
+    TimeoutGraceful(String),
 }
 
-/// Run a command with the given execution context
-pub fn run_command(mut cmd: Command, ctx: &ExecutionContext) -> ExecutionResult {
-    // Set working directory if specified
+/// Run a command with the given execution context.
+///
+/// This is a thin `block_on` wrapper around `async_executor::run_command_async` - the
+/// actual timeout/kill-grace/stdin handling lives there, built on `async-process` instead
+/// of a thread-per-command plus `mpsc` channel. Synchronous callers are unaffected: the
+/// signature and behavior are unchanged.
+pub fn run_command(cmd: Command, ctx: &ExecutionContext) -> ExecutionResult {
+    futures_lite::future::block_on(crate::async_executor::run_command_async(cmd, ctx))
+}
+
+/// Run a command with the given execution context, returning the structured
+/// `CommandResult` instead of flattening it to a single string. Prefer this over
+/// `run_command` when the caller can branch on `success`/`exit_code` and wants stdout and
+/// stderr kept separate.
+pub fn run_command_structured(mut cmd: Command, ctx: &ExecutionContext) -> CommandResult {
     if let Some(ref dir) = ctx.working_dir {
         cmd.current_dir(dir);
     }
-
-    // Set environment variables if specified
     if let Some(ref env) = ctx.env {
         for (key, value) in env {
             cmd.env(key, value);
         }
     }
 
-    // Execute with optional timeout
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    configure_stdin(&mut cmd, ctx);
+
     match ctx.timeout {
-        Some(timeout) => run_with_timeout(cmd, timeout),
-        None => run_without_timeout(cmd),
+        Some(timeout) => run_with_timeout_structured(cmd, timeout, ctx.kill_grace, ctx.stdin.clone()),
+        None => run_without_timeout_structured(cmd, ctx.stdin.clone()),
     }
 }
 
-fn run_without_timeout(mut cmd: Command) -> ExecutionResult {
-    match cmd.output() {
-        Ok(output) => output_to_result(output),
-        Err(e) => ExecutionResult::Error(format!("Failed to execute command: {}", e)),
+fn run_without_timeout_structured(mut cmd: Command, stdin: Option<String>) -> CommandResult {
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return CommandResult::error(format!("Failed to spawn command: {}", e)),
+    };
+    spawn_stdin_writer(child.stdin.take(), stdin);
+    match child.wait_with_output() {
+        Ok(output) => CommandResult::from_output(output),
+        Err(e) => CommandResult::error(format!("Failed to execute command: {}", e)),
     }
 }
 
-fn run_with_timeout(mut cmd: Command, timeout: Duration) -> ExecutionResult {
-    // Spawn the command
-    let child = match cmd.spawn() {
+fn run_with_timeout_structured(
+    mut cmd: Command,
+    timeout: Duration,
+    kill_grace: Option<Duration>,
+    stdin: Option<String>,
+) -> CommandResult {
+    isolate_process_group(&mut cmd);
+
+    let mut child = match cmd.spawn() {
         Ok(child) => child,
-        Err(e) => return ExecutionResult::Error(format!("Failed to spawn command: {}", e)),
+        Err(e) => return CommandResult::error(format!("Failed to spawn command: {}", e)),
     };
+    spawn_stdin_writer(child.stdin.take(), stdin);
 
-    // Use a channel to communicate between threads
     let (tx, rx) = mpsc::channel();
-
-    // Get the child's pid before moving it into the thread
     let child_id = child.id();
 
-    // Spawn a thread to wait for the child
     let handle = thread::spawn(move || {
         let result = child.wait_with_output();
         let _ = tx.send(result);
     });
 
-    // Wait for either completion or timeout
     match rx.recv_timeout(timeout) {
         Ok(Ok(output)) => {
             let _ = handle.join();
-            output_to_result(output)
+            CommandResult::from_output(output)
         }
         Ok(Err(e)) => {
             let _ = handle.join();
-            ExecutionResult::Error(format!("Command failed: {}", e))
+            CommandResult::error(format!("Command failed: {}", e))
         }
         Err(mpsc::RecvTimeoutError::Timeout) => {
-            // Kill the child process to avoid resource leaks
-            kill_process(child_id);
-            // Wait for the thread to finish (it will get an error from the killed process)
-            let _ = handle.join();
-            ExecutionResult::Timeout
+            let Some(grace) = kill_grace else {
+                kill_process_group(child_id);
+                let _ = handle.join();
+                return CommandResult::error("Command timed out".to_string());
+            };
+
+            // Ask the process group to exit on its own before forcing it.
+            terminate_process_group(child_id);
+            match rx.recv_timeout(grace) {
+                Ok(Ok(output)) => {
+                    let _ = handle.join();
+                    CommandResult::from_output(output)
+                }
+                Ok(Err(e)) => {
+                    let _ = handle.join();
+                    CommandResult::error(format!("Command failed: {}", e))
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    kill_process_group(child_id);
+                    let _ = handle.join();
+                    CommandResult::error("Command timed out and was force-killed after the grace period".to_string())
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    CommandResult::error("Command thread disconnected unexpectedly".to_string())
+                }
+            }
         }
         Err(mpsc::RecvTimeoutError::Disconnected) => {
-            ExecutionResult::Error("Command thread disconnected unexpectedly".to_string())
+            CommandResult::error("Command thread disconnected unexpectedly".to_string())
+        }
+    }
+}
+
+/// A chunk of output pulled from a streaming command, tagged by which pipe it came from
+/// so the caller can interleave or separate them as needed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputChunk {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// Size of each read from a streaming command's stdout/stderr pipe.
+const STREAM_CHUNK_SIZE: usize = 8192;
+
+/// Run a command, forwarding stdout/stderr to `chunks` as it's produced instead of
+/// buffering the whole output in memory, so a long-running or high-volume command can be
+/// observed incrementally rather than only once it exits. `max_bytes` caps the number of
+/// bytes forwarded per stream; once a stream hits the cap, further output from it is
+/// still drained (to avoid blocking the child on a full pipe) but no longer sent. Honors
+/// `ctx.timeout`/`ctx.kill_grace` the same way `run_command` does.
+pub fn run_command_streaming(
+    mut cmd: Command,
+    ctx: &ExecutionContext,
+    chunks: mpsc::Sender<OutputChunk>,
+    max_bytes: Option<usize>,
+) -> ExecutionResult {
+    if let Some(ref dir) = ctx.working_dir {
+        cmd.current_dir(dir);
+    }
+    if let Some(ref env) = ctx.env {
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+    }
+
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    configure_stdin(&mut cmd, ctx);
+    isolate_process_group(&mut cmd);
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return ExecutionResult::Error(format!("Failed to spawn command: {}", e)),
+    };
+    spawn_stdin_writer(child.stdin.take(), ctx.stdin.clone());
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    let stdout_handle = spawn_stream_reader(stdout, OutputChunk::Stdout, chunks.clone(), max_bytes);
+    let stderr_handle = spawn_stream_reader(stderr, OutputChunk::Stderr, chunks, max_bytes);
+
+    let (tx, rx) = mpsc::channel();
+    let child_id = child.id();
+    let handle = thread::spawn(move || {
+        let result = child.wait();
+        let _ = tx.send(result);
+    });
+
+    let wait_result = match ctx.timeout {
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(status) => status,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                let Some(grace) = ctx.kill_grace else {
+                    kill_process_group(child_id);
+                    let _ = handle.join();
+                    let _ = stdout_handle.join();
+                    let _ = stderr_handle.join();
+                    return ExecutionResult::Timeout;
+                };
+
+                terminate_process_group(child_id);
+                match rx.recv_timeout(grace) {
+                    Ok(Ok(_status)) => {
+                        let _ = handle.join();
+                        let _ = stdout_handle.join();
+                        let _ = stderr_handle.join();
+                        return ExecutionResult::TimeoutGraceful(String::new());
+                    }
+                    Ok(status) => status,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        kill_process_group(child_id);
+                        let _ = handle.join();
+                        let _ = stdout_handle.join();
+                        let _ = stderr_handle.join();
+                        return ExecutionResult::Timeout;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        let _ = stdout_handle.join();
+                        let _ = stderr_handle.join();
+                        return ExecutionResult::Error(
+                            "Command thread disconnected unexpectedly".to_string(),
+                        );
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                let _ = stdout_handle.join();
+                let _ = stderr_handle.join();
+                return ExecutionResult::Error("Command thread disconnected unexpectedly".to_string());
+            }
+        },
+        None => rx.recv().unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "command thread disconnected unexpectedly",
+            ))
+        }),
+    };
+
+    let _ = handle.join();
+    let _ = stdout_handle.join();
+    let _ = stderr_handle.join();
+
+    match wait_result {
+        Ok(status) if status.success() => ExecutionResult::Success(String::new()),
+        Ok(status) => ExecutionResult::Error(format!("Error: command exited with {}", status)),
+        Err(e) => ExecutionResult::Error(format!("Command failed: {}", e)),
+    }
+}
+
+/// Spawn a thread that reads `source` in fixed-size chunks, tags each with `tag`, and
+/// forwards it over `sender` until EOF. Once `max_bytes` has been forwarded, remaining
+/// output is still read (so the child never blocks on a full pipe) but is discarded
+/// instead of being sent, capping memory use against a runaway producer.
+fn spawn_stream_reader(
+    mut source: impl Read + Send + 'static,
+    tag: fn(Vec<u8>) -> OutputChunk,
+    sender: mpsc::Sender<OutputChunk>,
+    max_bytes: Option<usize>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut sent = 0usize;
+        let mut buf = [0u8; STREAM_CHUNK_SIZE];
+        loop {
+            match source.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let remaining = match max_bytes {
+                        Some(cap) => cap.saturating_sub(sent),
+                        None => n,
+                    };
+                    let forwarded = n.min(remaining);
+                    if forwarded > 0 {
+                        sent += forwarded;
+                        if sender.send(tag(buf[..forwarded].to_vec())).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(_) => break,
+            }
         }
+    })
+}
+
+/// If `ctx.stdin` is set, pipe `cmd`'s stdin so the caller can feed input to commands
+/// that read from it (e.g. `cat`, formatters, `jq`). Must be called before `spawn`.
+fn configure_stdin(cmd: &mut Command, ctx: &ExecutionContext) {
+    if ctx.stdin.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+}
+
+/// Write `data` (if any) to the spawned child's stdin on a dedicated thread, then drop
+/// the handle to close the pipe and signal EOF. Writing must happen off the calling
+/// thread: if the child's stdout/stderr pipes fill up before it finishes reading stdin,
+/// writing inline here would deadlock waiting for the child to drain them.
+fn spawn_stdin_writer(
+    stdin: Option<std::process::ChildStdin>,
+    data: Option<String>,
+) -> Option<thread::JoinHandle<()>> {
+    match (stdin, data) {
+        (Some(mut pipe), Some(data)) => Some(thread::spawn(move || {
+            use std::io::Write;
+            let _ = pipe.write_all(data.as_bytes());
+        })),
+        _ => None,
     }
 }
 
-/// Kill a process by its ID
-fn kill_process(pid: u32) {
+/// Put `cmd`'s child into its own process group (Unix) or process group marker
+/// (Windows) before spawning, so a timeout can terminate the whole tree instead of just
+/// the direct child - a command that itself forks subprocesses (e.g. `sh -c "foo &
+/// bar"`) would otherwise leave those orphaned and running after the parent is killed.
+fn isolate_process_group(cmd: &mut Command) {
     #[cfg(unix)]
     {
-        let _ = Command::new("kill")
-            .args(["-9", &pid.to_string()])
-            .output();
+        // pgid 0 makes the child its own process group leader (pgid == its pid),
+        // equivalent to calling setpgid(0, 0) right after fork.
+        cmd.process_group(0);
     }
     #[cfg(windows)]
     {
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+/// Kill a command's entire process group/tree by its (leader) PID, rather than just the
+/// single process - see `isolate_process_group` for why a single-PID kill leaks
+/// orphaned grandchildren.
+pub(crate) fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+        // A negative PID targets the whole process group instead of a single process,
+        // which only works because isolate_process_group made this child its own group
+        // leader (pgid == pid).
+        let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGKILL);
+    }
+    #[cfg(windows)]
+    {
+        // /T asks taskkill to terminate the whole process tree rooted at pid, which
+        // covers descendants started within the CREATE_NEW_PROCESS_GROUP marker.
         let _ = Command::new("taskkill")
-            .args(["/F", "/PID", &pid.to_string()])
+            .args(["/F", "/T", "/PID", &pid.to_string()])
             .output();
     }
 }
 
-fn output_to_result(output: Output) -> ExecutionResult {
-    if output.status.success() {
-        ExecutionResult::Success(String::from_utf8_lossy(&output.stdout).into_owned())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if stderr.is_empty() {
-            ExecutionResult::Error(format!("Error: {}", stdout))
-        } else {
-            ExecutionResult::Error(format!("Error: {}", stderr))
-        }
+/// Ask a command's entire process group/tree to exit on its own - SIGTERM on Unix, a
+/// best-effort graceful `taskkill` (no `/F`) on Windows - without forcing termination.
+/// Used as the first phase of a two-phase shutdown: give the process a chance to flush
+/// buffers or clean up before `kill_process_group` escalates to a hard kill.
+pub(crate) fn terminate_process_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+        let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGTERM);
+    }
+    #[cfg(windows)]
+    {
+        // Without /F, taskkill asks the process to close rather than forcing it. This
+        // is best-effort: console processes don't always honor it the way GUI windows
+        // do, which is an inherent limitation without a console-control-event API.
+        let _ = Command::new("taskkill")
+            .args(["/T", "/PID", &pid.to_string()])
+            .output();
     }
 }
 
@@ -119,6 +480,7 @@ impl ExecutionResult {
             ExecutionResult::Success(s) => s,
             ExecutionResult::Error(s) => s,
             ExecutionResult::Timeout => "Error: Command timed out".to_string(),
+            ExecutionResult::TimeoutGraceful(s) => format!("Error: Command timed out (exited after SIGTERM): {}", s),
         }
     }
 }
@@ -127,6 +489,7 @@ impl ExecutionResult {
 mod tests {
     use super::*;
     use std::collections::HashMap;
+    use std::path::Path;
 
     #[test]
     fn test_run_command_simple() {
@@ -187,6 +550,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_command_resolves_known_binary() {
+        let cmd = create_command("echo").expect("echo should be on PATH");
+        assert!(Path::new(cmd.get_program()).is_absolute());
+    }
+
+    #[test]
+    fn test_create_command_rejects_unknown_binary() {
+        assert!(create_command("definitely-not-a-real-binary-xyz").is_err());
+    }
+
     #[test]
     fn test_run_command_error() {
         let cmd = Command::new("ls");
@@ -199,4 +573,215 @@ mod tests {
             _ => panic!("Expected error"),
         }
     }
+
+    #[test]
+    fn test_run_command_structured_success_separates_stdout_and_exit_code() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("hello");
+        let ctx = ExecutionContext::default();
+        let result = run_command_structured(cmd, &ctx);
+        assert!(result.success);
+        assert_eq!(result.exit_code, Some(0));
+        assert!(result.stdout.contains("hello"));
+        assert_eq!(result.stderr, "");
+    }
+
+    #[test]
+    fn test_run_command_structured_failure_reports_exit_code() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo oops >&2; exit 3"]);
+        let ctx = ExecutionContext::default();
+        let result = run_command_structured(cmd, &ctx);
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(3));
+        assert!(result.stderr.contains("oops"));
+    }
+
+    #[test]
+    fn test_run_command_structured_timeout_has_no_exit_code() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let result = run_command_structured(cmd, &ctx);
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_timeout_kills_forked_grandchildren() {
+        // `sleep 1234` in the background is a duration distinctive enough that it
+        // shouldn't collide with anything else running in the test environment.
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 1234 & sleep 10"]);
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let result = run_command(cmd, &ctx);
+        assert!(matches!(result, ExecutionResult::Timeout));
+
+        // Give SIGKILL a moment to land, then confirm the backgrounded grandchild -
+        // not just the `sh` parent - was also killed as part of its process group.
+        thread::sleep(Duration::from_millis(300));
+        let ps = Command::new("ps").args(["ax"]).output().unwrap();
+        let ps_output = String::from_utf8_lossy(&ps.stdout);
+        assert!(!ps_output.contains("sleep 1234"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_timeout_with_grace_exits_gracefully() {
+        // Default SIGTERM disposition terminates `sleep` almost immediately, so a grace
+        // period comfortably longer than that should observe the graceful exit path
+        // instead of needing to escalate to SIGKILL.
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            kill_grace: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let result = run_command(cmd, &ctx);
+        assert!(matches!(result, ExecutionResult::TimeoutGraceful(_)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_timeout_with_grace_escalates_if_sigterm_ignored() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "trap '' TERM; sleep 10"]);
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            kill_grace: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let result = run_command(cmd, &ctx);
+        assert!(matches!(result, ExecutionResult::Timeout));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_structured_timeout_with_grace_exits_gracefully() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            kill_grace: Some(Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let result = run_command_structured(cmd, &ctx);
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_run_command_streaming_forwards_chunks_incrementally() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'hello'; printf 'world' >&2"]);
+        let ctx = ExecutionContext::default();
+        let (tx, rx) = mpsc::channel();
+        let result = run_command_streaming(cmd, &ctx, tx, None);
+        assert!(matches!(result, ExecutionResult::Success(_)));
+
+        let received: Vec<OutputChunk> = rx.try_iter().collect();
+        let stdout: Vec<u8> = received
+            .iter()
+            .filter_map(|c| match c {
+                OutputChunk::Stdout(b) => Some(b.clone()),
+                OutputChunk::Stderr(_) => None,
+            })
+            .flatten()
+            .collect();
+        let stderr: Vec<u8> = received
+            .iter()
+            .filter_map(|c| match c {
+                OutputChunk::Stderr(b) => Some(b.clone()),
+                OutputChunk::Stdout(_) => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(stdout, b"hello");
+        assert_eq!(stderr, b"world");
+    }
+
+    #[test]
+    fn test_run_command_streaming_caps_forwarded_bytes() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf '0123456789'"]);
+        let ctx = ExecutionContext::default();
+        let (tx, rx) = mpsc::channel();
+        let result = run_command_streaming(cmd, &ctx, tx, Some(4));
+        assert!(matches!(result, ExecutionResult::Success(_)));
+
+        let forwarded: usize = rx
+            .try_iter()
+            .map(|c| match c {
+                OutputChunk::Stdout(b) | OutputChunk::Stderr(b) => b.len(),
+            })
+            .sum();
+        assert!(forwarded <= 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_streaming_timeout_kills_process() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let ctx = ExecutionContext {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let (tx, _rx) = mpsc::channel();
+        let result = run_command_streaming(cmd, &ctx, tx, None);
+        assert!(matches!(result, ExecutionResult::Timeout));
+    }
+
+    #[test]
+    fn test_run_command_feeds_stdin_to_cat() {
+        let cmd = Command::new("cat");
+        let ctx = ExecutionContext {
+            stdin: Some("hello from stdin".to_string()),
+            ..Default::default()
+        };
+        let result = run_command(cmd, &ctx);
+        match result {
+            ExecutionResult::Success(s) => assert_eq!(s, "hello from stdin"),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_structured_feeds_stdin_to_cat() {
+        let cmd = Command::new("cat");
+        let ctx = ExecutionContext {
+            stdin: Some("structured stdin".to_string()),
+            ..Default::default()
+        };
+        let result = run_command_structured(cmd, &ctx);
+        assert!(result.success);
+        assert_eq!(result.stdout, "structured stdin");
+    }
+
+
+    #[test]
+    fn test_command_result_into_string_matches_legacy_formatting() {
+        let success = CommandResult {
+            stdout: "hello\n".to_string(),
+            stderr: String::new(),
+            exit_code: Some(0),
+            success: true,
+        };
+        assert_eq!(success.into_string(), "hello\n");
+
+        let failure = CommandResult {
+            stdout: String::new(),
+            stderr: "boom".to_string(),
+            exit_code: Some(1),
+            success: false,
+        };
+        assert_eq!(failure.into_string(), "Error: boom");
+    }
 }
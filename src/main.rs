@@ -1,11 +1,17 @@
+mod async_executor;
+mod cache;
 mod executor;
+mod policy;
 mod request;
 mod security;
 mod server;
 mod tools;
+mod transport;
+mod watch;
 
 use rmcp::{transport::stdio, ServiceExt};
 use server::CommandRunnerServer;
+use transport::Transport;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -14,6 +20,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_ansi(false)
         .init();
 
-    CommandRunnerServer::new().serve(stdio()).await?.waiting().await?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match Transport::from_env_and_args(&args) {
+        Transport::Stdio => {
+            CommandRunnerServer::new().serve(stdio()).await?.waiting().await?;
+        }
+        Transport::Sse { bind } => serve_sse(&bind).await?,
+    }
+
+    Ok(())
+}
+
+/// Serve the command runner over HTTP using Server-Sent Events, so the server can be
+/// hosted as a shared network service that multiple agents connect to concurrently.
+async fn serve_sse(bind: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use rmcp::transport::sse_server::SseServer;
+
+    let ct = SseServer::serve(bind.parse()?)
+        .await?
+        .with_service(CommandRunnerServer::new);
+
+    eprintln!("command-runner-mcp-server listening on {} (sse)", bind);
+    tokio::signal::ctrl_c().await?;
+    ct.cancel();
     Ok(())
 }
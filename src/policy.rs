@@ -0,0 +1,193 @@
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+use crate::security::ValidationError;
+
+/// Per-tool policy: which subcommands/arguments a tool may run and which paths it may
+/// touch. Every field is optional in the config file (`#[serde(default)]`) so an operator
+/// can override just the parts of a tool's policy they care about.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ToolPolicy {
+    /// Subcommands this tool is allowed to run. Empty means "no subcommand concept"
+    /// (e.g. ls_tool), not "nothing allowed".
+    pub allowed_subcommands: Vec<String>,
+    /// Regex patterns an argument must match at least one of. Empty means any argument
+    /// is allowed (still subject to the usual shell-injection/flag checks).
+    pub allowed_arg_patterns: Vec<String>,
+    /// Path prefixes this tool may not read from or write to, in addition to the
+    /// deployment-wide `BLOCKED_PATHS` environment variable.
+    pub blocked_paths: Vec<String>,
+    /// Whether ".." parent-directory traversal is permitted in paths this tool accepts.
+    pub allow_traversal: bool,
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_subcommands: Vec::new(),
+            allowed_arg_patterns: Vec::new(),
+            blocked_paths: Vec::new(),
+            allow_traversal: false,
+        }
+    }
+}
+
+impl ToolPolicy {
+    /// Whether `arg` satisfies this policy's `allowed_arg_patterns`. An empty pattern
+    /// list imposes no additional restriction.
+    pub fn arg_allowed(&self, arg: &str) -> bool {
+        if self.allowed_arg_patterns.is_empty() {
+            return true;
+        }
+        self.allowed_arg_patterns.iter().any(|pattern| {
+            regex::Regex::new(pattern)
+                .map(|re| re.is_match(arg))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Build the `DisallowedSubcommand` error for a subcommand this policy rejects.
+    pub fn reject_subcommand(&self, subcommand: &str) -> ValidationError {
+        ValidationError::DisallowedSubcommand {
+            subcommand: subcommand.to_string(),
+            allowed: self.allowed_subcommands.join(", "),
+        }
+    }
+}
+
+/// Full policy for the server: one `ToolPolicy` per tool. Loaded once at startup from a
+/// TOML or YAML file (see `load_policy`); defaults to `Policy::default()` when no
+/// config file is present.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    pub git: ToolPolicy,
+    pub ls: ToolPolicy,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            git: ToolPolicy {
+                allowed_subcommands: vec![
+                    "status".to_string(),
+                    "add".to_string(),
+                    "commit".to_string(),
+                    "checkout".to_string(),
+                    "log".to_string(),
+                    "diff".to_string(),
+                    "show".to_string(),
+                    "branch".to_string(),
+                    "rev-parse".to_string(),
+                ],
+                allowed_arg_patterns: Vec::new(),
+                blocked_paths: Vec::new(),
+                allow_traversal: false,
+            },
+            ls: ToolPolicy {
+                allowed_subcommands: Vec::new(),
+                allowed_arg_patterns: Vec::new(),
+                blocked_paths: Vec::new(),
+                allow_traversal: false,
+            },
+        }
+    }
+}
+
+/// Parse a policy file's contents, dispatching on its extension. Returns `None` if the
+/// extension is unrecognized or parsing fails, so the caller can fall back to defaults.
+fn parse_policy(contents: &str, path: &str) -> Option<Policy> {
+    if path.ends_with(".toml") {
+        toml::from_str(contents).ok()
+    } else if path.ends_with(".yaml") || path.ends_with(".yml") {
+        serde_yaml::from_str(contents).ok()
+    } else {
+        None
+    }
+}
+
+/// Load the policy configured via the `POLICY_FILE` environment variable (a path to a
+/// `.toml`, `.yaml`, or `.yml` file). Falls back to `Policy::default()` - the built-in
+/// git subcommand allowlist and no extra ls restrictions - if the variable is unset, the
+/// file can't be read, or it fails to parse.
+fn load_policy() -> Policy {
+    let Some(path) = std::env::var("POLICY_FILE").ok() else {
+        return Policy::default();
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return Policy::default();
+    };
+    parse_policy(&contents, &path).unwrap_or_default()
+}
+
+static POLICY: LazyLock<Policy> = LazyLock::new(load_policy);
+
+/// The server's loaded policy, read once at startup.
+pub fn policy() -> &'static Policy {
+    &POLICY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_current_git_subcommands() {
+        let policy = Policy::default();
+        assert_eq!(
+            policy.git.allowed_subcommands,
+            vec!["status", "add", "commit", "checkout", "log", "diff", "show", "branch", "rev-parse"]
+        );
+    }
+
+    #[test]
+    fn test_default_policy_blocks_ls_traversal() {
+        assert!(!Policy::default().ls.allow_traversal);
+    }
+
+    #[test]
+    fn test_tool_policy_arg_allowed_empty_patterns_allows_anything() {
+        let policy = ToolPolicy::default();
+        assert!(policy.arg_allowed("anything at all"));
+    }
+
+    #[test]
+    fn test_tool_policy_arg_allowed_respects_patterns() {
+        let policy = ToolPolicy {
+            allowed_arg_patterns: vec![r"^[\w./-]+$".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.arg_allowed("src/main.rs"));
+        assert!(!policy.arg_allowed("src/main.rs; rm -rf /"));
+    }
+
+    #[test]
+    fn test_parse_policy_toml() {
+        let toml_src = r#"
+[git]
+allowed_subcommands = ["status", "log"]
+"#;
+        let policy = parse_policy(toml_src, "policy.toml").expect("should parse");
+        assert_eq!(policy.git.allowed_subcommands, vec!["status", "log"]);
+        // Unspecified sections fall back to their `Policy::default()` entry
+        assert!(policy.ls.allowed_subcommands.is_empty());
+    }
+
+    #[test]
+    fn test_parse_policy_yaml() {
+        let yaml_src = "git:\n  allowed_subcommands:\n    - status\n    - log\n";
+        let policy = parse_policy(yaml_src, "policy.yaml").expect("should parse");
+        assert_eq!(policy.git.allowed_subcommands, vec!["status", "log"]);
+    }
+
+    #[test]
+    fn test_parse_policy_rejects_unknown_extension() {
+        assert!(parse_policy("git: {}", "policy.conf").is_none());
+    }
+
+    #[test]
+    fn test_parse_policy_rejects_malformed_toml() {
+        assert!(parse_policy("this is not valid toml [[[", "policy.toml").is_none());
+    }
+}
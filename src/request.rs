@@ -12,6 +12,13 @@ pub struct ExecutionContext {
     pub timeout: Option<Duration>,
     pub working_dir: Option<String>,
     pub env: Option<HashMap<String, String>>,
+    /// Grace period after a timed-out command is sent SIGTERM before escalating to
+    /// SIGKILL. `None` skips the grace period entirely and kills immediately, matching
+    /// the pre-existing behavior.
+    pub kill_grace: Option<Duration>,
+    /// Input to write to the command's stdin before closing it. `None` leaves stdin
+    /// untouched (inherited from the parent), matching the pre-existing behavior.
+    pub stdin: Option<String>,
 }
 
 /// Available transformation operations
@@ -19,19 +26,31 @@ pub struct ExecutionContext {
 #[serde(rename_all = "snake_case")]
 pub enum Transformation {
     Grep,
+    Glob,
+    Replace,
+    Cut,
     Sort,
+    NumericSort,
     Unique,
+    UniqueGlobal,
     Head,
     Tail,
+    Match,
 }
 
 /// Default transformation order
 const DEFAULT_TRANSFORM_ORDER: &[Transformation] = &[
     Transformation::Grep,
+    Transformation::Glob,
+    Transformation::Replace,
+    Transformation::Cut,
     Transformation::Sort,
+    Transformation::NumericSort,
     Transformation::Unique,
+    Transformation::UniqueGlobal,
     Transformation::Head,
     Transformation::Tail,
+    Transformation::Match,
 ];
 
 /// A wrapper that adds common fields to any tool request.
@@ -46,6 +65,11 @@ pub struct ToolRequest<T> {
     #[serde(default)]
     pub invert_grep: Option<bool>,
 
+    /// Optional shell-style glob pattern (`*`, `?`, `[abc]`, `**`) to filter output
+    /// lines, for callers that reach for glob syntax more naturally than regex
+    #[serde(default)]
+    pub glob_pattern: Option<String>,
+
     /// Return only the first N lines of output
     #[serde(default)]
     pub head: Option<usize>,
@@ -54,18 +78,59 @@ pub struct ToolRequest<T> {
     #[serde(default)]
     pub tail: Option<usize>,
 
+    /// Regex pattern for per-line substitution, applied with `replace_with` (supports
+    /// `$1`-style capture-group backreferences)
+    #[serde(default)]
+    pub replace_pattern: Option<String>,
+
+    /// Replacement text for `replace_pattern`
+    #[serde(default)]
+    pub replace_with: Option<String>,
+
+    /// Delimiter to split each line on for `cut_fields` (like `cut -d`/`awk -F`).
+    /// Defaults to splitting on whitespace when omitted.
+    #[serde(default)]
+    pub cut_delim: Option<String>,
+
+    /// 1-indexed fields to keep from each delimiter-split line (like `awk '{print $1,$3}'`)
+    #[serde(default)]
+    pub cut_fields: Option<Vec<usize>>,
+
     /// Sort output lines alphabetically
     #[serde(default)]
     pub sort: Option<bool>,
 
+    /// Sort output lines numerically, by the first number found in each line
+    #[serde(default)]
+    pub numeric_sort: Option<bool>,
+
+    /// Reverse the result of `sort`/`numeric_sort`
+    #[serde(default)]
+    pub reverse: Option<bool>,
+
     /// Remove duplicate consecutive lines (like uniq)
     #[serde(default)]
     pub unique: Option<bool>,
 
+    /// Remove duplicate lines anywhere in the output, not just adjacent ones
+    #[serde(default)]
+    pub unique_global: Option<bool>,
+
     /// Timeout in milliseconds for command execution (default: 180000 = 3 minutes)
     #[serde(default)]
     pub timeout_ms: Option<u64>,
 
+    /// Grace period in milliseconds between sending SIGTERM to a timed-out command and
+    /// escalating to SIGKILL if it hasn't exited. Omitted/unset skips the grace period
+    /// and kills immediately, as before.
+    #[serde(default)]
+    pub kill_grace_ms: Option<u64>,
+
+    /// Input to feed to the command's stdin before closing it, for tools that read from
+    /// stdin (e.g. `cat`, formatters, `jq`). Omitted leaves stdin untouched.
+    #[serde(default)]
+    pub stdin: Option<String>,
+
     /// Working directory for command execution
     #[serde(default)]
     pub working_dir: Option<String>,
@@ -79,6 +144,38 @@ pub struct ToolRequest<T> {
     #[serde(default)]
     pub transform_order: Option<Vec<Transformation>>,
 
+    /// Expected output template using cargo's lenient `[..]` wildcard line-matching.
+    /// Each template line is compared against the corresponding output line; `[..]`
+    /// matches any text. Supports `[EXE]` (".exe" on Windows, empty elsewhere) and
+    /// `[ROOT]` (the effective working_dir) substitutions. On mismatch, returns
+    /// `Error: mismatch at line N` instead of the command output.
+    #[serde(default)]
+    pub match_template: Option<String>,
+
+    /// Whether to reuse a cached result for an identical prior invocation (same tool,
+    /// request, working_dir and env) instead of re-executing. Default: true.
+    #[serde(default)]
+    pub cache: Option<bool>,
+
+    /// Time-to-live in milliseconds for a cached result. Default: 60000 (1 minute).
+    #[serde(default)]
+    pub cache_ttl_ms: Option<u64>,
+
+    /// If true, re-run the command whenever files under `watch_paths` change, until the
+    /// watch session's deadline or iteration cap is reached (see `watch::watch_and_rerun`).
+    #[serde(default)]
+    pub watch: Option<bool>,
+
+    /// Paths to watch recursively for changes when `watch` is set. Defaults to
+    /// `working_dir` (or the current directory) when omitted.
+    #[serde(default)]
+    pub watch_paths: Option<Vec<String>>,
+
+    /// Debounce window in milliseconds for batching filesystem events before re-running.
+    /// Default: 500ms.
+    #[serde(default)]
+    pub watch_ms: Option<u64>,
+
     #[serde(flatten)]
     pub inner: T,
 }
@@ -93,6 +190,34 @@ impl<T> ToolRequest<T> {
     /// Default timeout in milliseconds (180 seconds)
     const DEFAULT_TIMEOUT_MS: u64 = 180_000;
 
+    /// Default debounce window for batching filesystem events in watch mode
+    const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 500;
+
+    /// Default time-to-live for a cached result
+    pub const DEFAULT_CACHE_TTL_MS: u64 = 60_000;
+
+    /// Whether caching is enabled for this request (default: true)
+    pub fn cache_enabled(&self) -> bool {
+        self.cache.unwrap_or(true)
+    }
+
+    /// The TTL to store a cached result with
+    pub fn cache_ttl(&self) -> u64 {
+        self.cache_ttl_ms.unwrap_or(Self::DEFAULT_CACHE_TTL_MS)
+    }
+
+    /// The debounce window to use for watch mode
+    pub fn watch_debounce(&self) -> Duration {
+        Duration::from_millis(self.watch_ms.unwrap_or(Self::DEFAULT_WATCH_DEBOUNCE_MS))
+    }
+
+    /// The paths to watch in watch mode, defaulting to `working_dir` or the current directory
+    pub fn watch_paths(&self) -> Vec<String> {
+        self.watch_paths.clone().unwrap_or_else(|| {
+            vec![self.working_dir.clone().unwrap_or_else(|| ".".to_string())]
+        })
+    }
+
     /// Extract execution context for command execution
     pub fn execution_context(&self) -> ExecutionContext {
         let timeout_ms = self.timeout_ms.unwrap_or(Self::DEFAULT_TIMEOUT_MS);
@@ -100,6 +225,8 @@ impl<T> ToolRequest<T> {
             timeout: Some(Duration::from_millis(timeout_ms)),
             working_dir: self.working_dir.clone(),
             env: self.env.clone(),
+            kill_grace: self.kill_grace_ms.map(Duration::from_millis),
+            stdin: self.stdin.clone(),
         }
     }
 
@@ -116,10 +243,16 @@ impl<T> ToolRequest<T> {
         for transform in order {
             result = match transform {
                 Transformation::Grep => self.apply_grep(result),
+                Transformation::Glob => self.apply_glob(result),
+                Transformation::Replace => self.apply_replace(result),
+                Transformation::Cut => self.apply_cut(result),
                 Transformation::Sort => self.apply_sort(result),
+                Transformation::NumericSort => self.apply_numeric_sort(result),
                 Transformation::Unique => self.apply_unique(result),
+                Transformation::UniqueGlobal => self.apply_unique_global(result),
                 Transformation::Head => self.apply_head(result),
                 Transformation::Tail => self.apply_tail(result),
+                Transformation::Match => self.apply_match(result),
             };
 
             // Stop on error
@@ -152,10 +285,87 @@ impl<T> ToolRequest<T> {
         }
     }
 
+    fn apply_glob(&self, output: String) -> String {
+        match &self.glob_pattern {
+            Some(pattern) => {
+                let compiled = match glob::Pattern::new(pattern) {
+                    Ok(p) => p,
+                    Err(e) => return format!("Error: Invalid glob pattern: {}", e),
+                };
+                output
+                    .lines()
+                    .filter(|line| compiled.matches(line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            None => output,
+        }
+    }
+
+    fn apply_replace(&self, output: String) -> String {
+        match (&self.replace_pattern, &self.replace_with) {
+            (Some(pattern), Some(replacement)) => {
+                let regex = match Regex::new(pattern) {
+                    Ok(r) => r,
+                    Err(e) => return format!("Error: Invalid replace pattern: {}", e),
+                };
+                output
+                    .lines()
+                    .map(|line| regex.replace_all(line, replacement.as_str()).into_owned())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            _ => output,
+        }
+    }
+
+    fn apply_cut(&self, output: String) -> String {
+        match &self.cut_fields {
+            Some(fields) if !fields.is_empty() => {
+                output
+                    .lines()
+                    .map(|line| {
+                        let parts: Vec<&str> = match &self.cut_delim {
+                            Some(delim) => line.split(delim.as_str()).collect(),
+                            None => line.split_whitespace().collect(),
+                        };
+                        fields
+                            .iter()
+                            .filter_map(|&field| field.checked_sub(1).and_then(|i| parts.get(i)).copied())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            _ => output,
+        }
+    }
+
     fn apply_sort(&self, output: String) -> String {
         if self.sort.unwrap_or(false) {
             let mut lines: Vec<&str> = output.lines().collect();
             lines.sort();
+            if self.reverse.unwrap_or(false) {
+                lines.reverse();
+            }
+            lines.join("\n")
+        } else {
+            output
+        }
+    }
+
+    fn apply_numeric_sort(&self, output: String) -> String {
+        if self.numeric_sort.unwrap_or(false) {
+            let mut lines: Vec<&str> = output.lines().collect();
+            lines.sort_by(|a, b| {
+                first_number(a)
+                    .partial_cmp(&first_number(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            if self.reverse.unwrap_or(false) {
+                lines.reverse();
+            }
             lines.join("\n")
         } else {
             output
@@ -178,6 +388,19 @@ impl<T> ToolRequest<T> {
         }
     }
 
+    fn apply_unique_global(&self, output: String) -> String {
+        if self.unique_global.unwrap_or(false) {
+            let mut seen = std::collections::HashSet::new();
+            output
+                .lines()
+                .filter(|line| seen.insert(*line))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            output
+        }
+    }
+
     fn apply_head(&self, output: String) -> String {
         match self.head {
             Some(n) => {
@@ -201,6 +424,87 @@ impl<T> ToolRequest<T> {
             None => output,
         }
     }
+
+    fn apply_match(&self, output: String) -> String {
+        match &self.match_template {
+            Some(template) => {
+                let root = self.working_dir.as_deref().unwrap_or_default();
+                let substituted = substitute_named(template, root);
+                for (i, expected) in substituted.lines().enumerate() {
+                    match output.lines().nth(i) {
+                        Some(actual) if line_matches(expected, actual) => {}
+                        Some(actual) => {
+                            return format!(
+                                "Error: mismatch at line {}: expected `{}`, got `{}`",
+                                i + 1,
+                                expected,
+                                actual
+                            );
+                        }
+                        None => {
+                            return format!(
+                                "Error: mismatch at line {}: expected `{}`, got <no output>",
+                                i + 1,
+                                expected
+                            );
+                        }
+                    }
+                }
+                output
+            }
+            None => output,
+        }
+    }
+}
+
+/// First signed decimal number found in a line, for `NumericSort`. Lines with no
+/// number sort as `f64::MIN` so they consistently land at one end.
+fn first_number(line: &str) -> f64 {
+    static NUMBER_RE: std::sync::LazyLock<Regex> =
+        std::sync::LazyLock::new(|| Regex::new(r"-?\d+(\.\d+)?").unwrap());
+    NUMBER_RE
+        .find(line)
+        .and_then(|m| m.as_str().parse().ok())
+        .unwrap_or(f64::MIN)
+}
+
+/// Expand the named substitutions recognized in `[..]` match templates.
+fn substitute_named(template: &str, root: &str) -> String {
+    template
+        .replace("[EXE]", std::env::consts::EXE_SUFFIX)
+        .replace("[ROOT]", root)
+}
+
+/// Check whether `actual` satisfies cargo-style `[..]` wildcard matching against `template`.
+///
+/// The template is split on the literal token `[..]`; each resulting fragment must be found
+/// in `actual`, in order, starting its search at or after the end of the previous match. If
+/// the template doesn't end in `[..]`, the final fragment must also end exactly at the end of
+/// `actual`.
+fn line_matches(template: &str, actual: &str) -> bool {
+    if !template.contains("[..]") {
+        return template == actual;
+    }
+
+    let ends_with_wildcard = template.ends_with("[..]");
+    let fragments: Vec<&str> = template.split("[..]").collect();
+    let mut cursor = 0;
+
+    for (i, fragment) in fragments.iter().enumerate() {
+        if fragment.is_empty() {
+            continue;
+        }
+        let is_last = i == fragments.len() - 1;
+        if is_last && !ends_with_wildcard {
+            return actual[cursor..].ends_with(fragment);
+        }
+        match actual[cursor..].find(fragment) {
+            Some(pos) => cursor += pos + fragment.len(),
+            None => return false,
+        }
+    }
+
+    true
 }
 
 #[cfg(test)]
@@ -231,14 +535,30 @@ mod tests {
         ToolRequest {
             grep_pattern: grep_pattern.map(String::from),
             invert_grep,
+            glob_pattern: None,
+            replace_pattern: None,
+            replace_with: None,
+            cut_delim: None,
+            cut_fields: None,
             head,
             tail,
             sort,
+            numeric_sort: None,
+            reverse: None,
             unique,
+            unique_global: None,
             timeout_ms: None,
+            kill_grace_ms: None,
+            stdin: None,
             working_dir: None,
             env: None,
             transform_order,
+            match_template: None,
+            cache: None,
+            cache_ttl_ms: None,
+            watch: None,
+            watch_paths: None,
+            watch_ms: None,
             inner: LsRequest {
                 path: ".".to_string(),
             },
@@ -282,6 +602,128 @@ mod tests {
         assert_eq!(req.transform_output(output), "line1\nline3");
     }
 
+    // Glob tests
+    #[test]
+    fn test_glob_no_pattern() {
+        let req = make_request(None, None, None, None, None, None);
+        let output = "foo.rs\nbar.txt".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    #[test]
+    fn test_glob_with_pattern() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.glob_pattern = Some("*.rs".to_string());
+        let output = "foo.rs\nbar.txt\nbaz.rs".to_string();
+        assert_eq!(req.transform_output(output), "foo.rs\nbaz.rs");
+    }
+
+    #[test]
+    fn test_glob_invalid_pattern() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.glob_pattern = Some("[".to_string());
+        let output = "foo.rs".to_string();
+        let result = req.transform_output(output);
+        assert!(result.contains("Invalid glob pattern"));
+    }
+
+    // Replace tests
+    #[test]
+    fn test_replace_simple_substitution() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.replace_pattern = Some("foo".to_string());
+        req.replace_with = Some("bar".to_string());
+        let output = "foo1\nfoo2".to_string();
+        assert_eq!(req.transform_output(output), "bar1\nbar2");
+    }
+
+    #[test]
+    fn test_replace_capture_group_backreference() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.replace_pattern = Some(r"(\w+)@(\w+)".to_string());
+        req.replace_with = Some("$2@$1".to_string());
+        let output = "user@host".to_string();
+        assert_eq!(req.transform_output(output), "host@user");
+    }
+
+    #[test]
+    fn test_replace_invalid_pattern() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.replace_pattern = Some("[invalid".to_string());
+        req.replace_with = Some("x".to_string());
+        let result = req.transform_output("line".to_string());
+        assert!(result.contains("Invalid replace pattern"));
+    }
+
+    #[test]
+    fn test_replace_without_replace_with_is_noop() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.replace_pattern = Some("foo".to_string());
+        let output = "foo".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    // Cut tests
+    #[test]
+    fn test_cut_selects_fields_whitespace_delim() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.cut_fields = Some(vec![1, 3]);
+        let output = "a b c\nd e f".to_string();
+        assert_eq!(req.transform_output(output), "a c\nd f");
+    }
+
+    #[test]
+    fn test_cut_with_custom_delimiter() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.cut_delim = Some(",".to_string());
+        req.cut_fields = Some(vec![2]);
+        let output = "a,b,c".to_string();
+        assert_eq!(req.transform_output(output), "b");
+    }
+
+    #[test]
+    fn test_cut_out_of_range_field_is_skipped() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.cut_fields = Some(vec![1, 5]);
+        let output = "a b".to_string();
+        assert_eq!(req.transform_output(output), "a");
+    }
+
+    // NumericSort tests
+    #[test]
+    fn test_numeric_sort() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.numeric_sort = Some(true);
+        let output = "10 items\n2 items\n1 item".to_string();
+        assert_eq!(req.transform_output(output), "1 item\n2 items\n10 items");
+    }
+
+    #[test]
+    fn test_numeric_sort_reverse() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.numeric_sort = Some(true);
+        req.reverse = Some(true);
+        let output = "10 items\n2 items\n1 item".to_string();
+        assert_eq!(req.transform_output(output), "10 items\n2 items\n1 item");
+    }
+
+    #[test]
+    fn test_sort_reverse() {
+        let mut req = make_request(None, None, None, None, Some(true), None);
+        let output = "apple\nbanana\ncherry".to_string();
+        req.reverse = Some(true);
+        assert_eq!(req.transform_output(output), "cherry\nbanana\napple");
+    }
+
+    // UniqueGlobal tests
+    #[test]
+    fn test_unique_global_dedupes_non_adjacent() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.unique_global = Some(true);
+        let output = "a\nb\na\nc\nb".to_string();
+        assert_eq!(req.transform_output(output), "a\nb\nc");
+    }
+
     // Head/tail tests
     #[test]
     fn test_head() {
@@ -335,6 +777,125 @@ mod tests {
         assert_eq!(req.transform_output(output), "line1\nline2");
     }
 
+    // Match template tests
+    #[test]
+    fn test_match_no_template() {
+        let req = make_request(None, None, None, None, None, None);
+        let output = "line1\nline2".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    #[test]
+    fn test_match_exact_template() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.match_template = Some("line1\nline2".to_string());
+        let output = "line1\nline2".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    #[test]
+    fn test_match_wildcard_template() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.match_template = Some("Compiling foo v[..]\nFinished in [..]s".to_string());
+        let output = "Compiling foo v0.1.0\nFinished in 1.23s".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    #[test]
+    fn test_match_mismatch_reports_line_number() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.match_template = Some("line1\nexpected".to_string());
+        let output = "line1\nactual".to_string();
+        let result = req.transform_output(output);
+        assert!(result.contains("mismatch at line 2"));
+    }
+
+    #[test]
+    fn test_match_missing_output_line() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.match_template = Some("line1\nline2".to_string());
+        let output = "line1".to_string();
+        let result = req.transform_output(output);
+        assert!(result.contains("mismatch at line 2"));
+        assert!(result.contains("<no output>"));
+    }
+
+    #[test]
+    fn test_match_root_substitution() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.working_dir = Some("/tmp/project".to_string());
+        req.match_template = Some("[ROOT]/Cargo.toml".to_string());
+        let output = "/tmp/project/Cargo.toml".to_string();
+        assert_eq!(req.transform_output(output.clone()), output);
+    }
+
+    #[test]
+    fn test_line_matches_no_trailing_wildcard_requires_full_end() {
+        assert!(!line_matches("foo[..]bar", "foobazbarqux"));
+        assert!(line_matches("foo[..]bar", "foobazbar"));
+    }
+
+    // Cache tests
+    #[test]
+    fn test_cache_enabled_by_default() {
+        let req = make_request(None, None, None, None, None, None);
+        assert!(req.cache_enabled());
+    }
+
+    #[test]
+    fn test_cache_disabled_explicitly() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.cache = Some(false);
+        assert!(!req.cache_enabled());
+    }
+
+    #[test]
+    fn test_cache_ttl_defaults() {
+        let req = make_request(None, None, None, None, None, None);
+        assert_eq!(req.cache_ttl(), ToolRequest::<LsRequest>::DEFAULT_CACHE_TTL_MS);
+    }
+
+    #[test]
+    fn test_cache_ttl_custom() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.cache_ttl_ms = Some(5_000);
+        assert_eq!(req.cache_ttl(), 5_000);
+    }
+
+    // Watch mode tests
+    #[test]
+    fn test_watch_debounce_defaults() {
+        let req = make_request(None, None, None, None, None, None);
+        assert_eq!(req.watch_debounce(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_watch_debounce_custom() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.watch_ms = Some(100);
+        assert_eq!(req.watch_debounce(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_watch_paths_defaults_to_working_dir() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.working_dir = Some("/tmp/project".to_string());
+        assert_eq!(req.watch_paths(), vec!["/tmp/project".to_string()]);
+    }
+
+    #[test]
+    fn test_watch_paths_defaults_to_current_dir() {
+        let req = make_request(None, None, None, None, None, None);
+        assert_eq!(req.watch_paths(), vec![".".to_string()]);
+    }
+
+    #[test]
+    fn test_watch_paths_explicit() {
+        let mut req = make_request(None, None, None, None, None, None);
+        req.watch_paths = Some(vec!["/src".to_string(), "/tests".to_string()]);
+        assert_eq!(req.watch_paths(), vec!["/src".to_string(), "/tests".to_string()]);
+    }
+
     // Deserialization tests
     #[test]
     fn test_deserialize_with_all_fields() {
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 /// Characters that could be used for shell injection
@@ -10,19 +10,99 @@ const SHELL_INJECTION_CHARS: &[char] = &[
 /// Human-readable list of forbidden characters for error messages
 const SHELL_INJECTION_CHARS_DISPLAY: &str = "; | & $ ` ( ) { } [ ] < > ' \" \\ * ? ! #";
 
+/// Glob metacharacters that are legitimate in a glob pattern but would
+/// otherwise be flagged as shell injection attempts.
+const GLOB_PATTERN_CHARS: &[char] = &['*', '?', '[', ']'];
+
 /// Hint about available transformations for error messages
 const TRANSFORM_HINT: &str = "Use grep_pattern, head, tail, sort, or unique parameters to filter/transform output instead of shell operators.";
 
-/// Blocked paths loaded from BLOCKED_PATHS environment variable at startup.
-/// Format: semicolon-separated list of absolute paths, e.g., "/etc;/root;/home/user/.ssh"
-static BLOCKED_PATHS: LazyLock<Vec<String>> = LazyLock::new(|| {
-    std::env::var("BLOCKED_PATHS")
+/// Parse a semicolon-separated environment variable into a list of path entries, e.g.
+/// "/etc;/root;**/.ssh".
+fn parse_path_list(var: &str) -> Vec<String> {
+    std::env::var(var)
         .unwrap_or_default()
         .split(';')
         .filter(|s| !s.is_empty())
         .map(|s| s.trim().to_string())
         .collect()
-});
+}
+
+/// A single entry in a `PathMatcher`: either a plain path prefix (matched as an exact
+/// path or a directory ancestor) or a compiled gitignore/glob-style pattern (matched
+/// against the full normalized path).
+enum MatchEntry {
+    Prefix,
+    Glob(glob::Pattern),
+}
+
+/// An entry counts as a glob pattern if it contains a glob metacharacter; otherwise
+/// it's treated as a plain path prefix, preserving the original exact-or-ancestor
+/// matching behavior for entries like "/etc" or "/home/user/.ssh".
+fn is_glob_entry(entry: &str) -> bool {
+    entry.contains('*') || entry.contains('?') || entry.contains('[')
+}
+
+/// A compiled set of path-matching rules, built once (typically behind a `LazyLock`)
+/// from a list of plain prefixes and/or gitignore/glob-style patterns (`**/.ssh`,
+/// `*.pem`, `/home/*/.aws/**`), so patterns don't need to be recompiled on every check.
+pub(crate) struct PathMatcher {
+    entries: Vec<(String, MatchEntry)>,
+}
+
+impl PathMatcher {
+    /// Compile `patterns` into a matcher. A pattern that fails to compile as a glob
+    /// (malformed brackets, etc.) falls back to plain prefix matching rather than being
+    /// silently dropped, so a typo'd pattern still blocks at least its literal form.
+    pub(crate) fn compile(patterns: &[String]) -> Self {
+        let entries = patterns
+            .iter()
+            .map(|pattern| {
+                if is_glob_entry(pattern) {
+                    match glob::Pattern::new(pattern) {
+                        Ok(compiled) => (pattern.clone(), MatchEntry::Glob(compiled)),
+                        Err(_) => (pattern.clone(), MatchEntry::Prefix),
+                    }
+                } else {
+                    (pattern.clone(), MatchEntry::Prefix)
+                }
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Whether any entry matches `path_str` (a normalized absolute path), returning the
+    /// original pattern/prefix string that matched, for use in error messages.
+    pub(crate) fn matching(&self, path_str: &str) -> Option<String> {
+        for (raw, entry) in &self.entries {
+            let matched = match entry {
+                MatchEntry::Prefix => {
+                    path_str == raw || path_str.starts_with(&format!("{}/", raw))
+                }
+                MatchEntry::Glob(pattern) => pattern.matches(path_str),
+            };
+            if matched {
+                return Some(raw.clone());
+            }
+        }
+        None
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Blocked paths loaded from the BLOCKED_PATHS environment variable at startup.
+/// Format: semicolon-separated list of absolute paths or glob patterns, e.g.
+/// "/etc;/root;**/.ssh;*.pem".
+static BLOCKED_PATHS: LazyLock<PathMatcher> =
+    LazyLock::new(|| PathMatcher::compile(&parse_path_list("BLOCKED_PATHS")));
+
+/// Allowlist mode: when ALLOWED_PATHS is non-empty, any path that does not match one of
+/// its entries is rejected, regardless of BLOCKED_PATHS. Same format as BLOCKED_PATHS.
+static ALLOWED_PATHS: LazyLock<PathMatcher> =
+    LazyLock::new(|| PathMatcher::compile(&parse_path_list("ALLOWED_PATHS")));
 
 /// Environment variable names that could be used for code injection or privilege escalation
 const DANGEROUS_ENV_VARS: &[&str] = &[
@@ -56,6 +136,8 @@ pub enum ValidationError {
     PathTraversal(String),
     RelativeWorkingDir(String),
     DisallowedSubcommand { subcommand: String, allowed: String },
+    DisallowedFlag { flag: String, subcommand: String, allowed: String },
+    NotAllowed(String),
 }
 
 impl std::fmt::Display for ValidationError {
@@ -106,6 +188,20 @@ impl std::fmt::Display for ValidationError {
                     subcommand, allowed
                 )
             }
+            ValidationError::DisallowedFlag { flag, subcommand, allowed } => {
+                write!(
+                    f,
+                    "Error: Flag '{}' is not allowed for subcommand '{}'. Allowed flags: {}",
+                    flag, subcommand, allowed
+                )
+            }
+            ValidationError::NotAllowed(path) => {
+                write!(
+                    f,
+                    "Error: Path '{}' is not under any allowed path (ALLOWED_PATHS allowlist is active).",
+                    path
+                )
+            }
         }
     }
 }
@@ -130,6 +226,18 @@ pub fn validate_argument(arg: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate an argument that is expected to be a glob pattern, allowing the
+/// glob metacharacters (`* ? [ ]`) through while still rejecting everything
+/// else in `SHELL_INJECTION_CHARS`.
+pub fn validate_glob_pattern(s: &str) -> Result<(), ValidationError> {
+    if s.chars()
+        .any(|c| SHELL_INJECTION_CHARS.contains(&c) && !GLOB_PATTERN_CHARS.contains(&c))
+    {
+        return Err(ValidationError::ShellInjection(s.to_string()));
+    }
+    Ok(())
+}
+
 /// Check if a string looks like a command-line flag (starts with -)
 pub fn is_flag_like(s: &str) -> bool {
     s.starts_with('-') && s != "-" && s != "--"
@@ -144,9 +252,19 @@ pub fn validate_not_flag(arg: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
-/// Check if a path contains ".." (parent directory traversal)
+/// Split `path` into its components on path separators, ignoring empty segments (e.g.
+/// a leading `/` or doubled separators). Only `/` is treated as a separator on Unix;
+/// `\` is also recognized on Windows, where it's the native separator rather than an
+/// ordinary filename character.
+fn path_components(path: &str) -> impl Iterator<Item = &str> {
+    let separators: &[char] = if cfg!(windows) { &['/', '\\'] } else { &['/'] };
+    path.split(separators).filter(|c| !c.is_empty())
+}
+
+/// Check if a path contains ".." as a parent-directory-traversal *component*, not
+/// merely as a substring - so a filename like `my..file` isn't a false positive.
 pub fn contains_traversal(path: &str) -> bool {
-    path.contains("..")
+    path_components(path).any(|c| c == "..")
 }
 
 /// Validate that a path doesn't contain ".." traversal
@@ -157,9 +275,42 @@ pub fn validate_no_traversal(path: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
-/// Validate that a path is absolute (starts with '/')
+/// Whether `path` is absolute. On Unix, that means a leading `/`. On Windows, it also
+/// recognizes a drive letter (`C:\` or `C:/`), a `\\?\` verbatim prefix, and a UNC root
+/// (`\\server\share`).
+fn is_absolute_path(path: &str) -> bool {
+    if path.starts_with('/') {
+        return true;
+    }
+    #[cfg(windows)]
+    {
+        return is_windows_absolute_path(path);
+    }
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Windows-specific absoluteness check, see `is_absolute_path`. Gated behind
+/// `cfg(windows)` so Unix path validation is unchanged.
+#[cfg(windows)]
+fn is_windows_absolute_path(path: &str) -> bool {
+    if path.starts_with(r"\\") {
+        // \\?\ verbatim prefix or a UNC root like \\server\share
+        return true;
+    }
+    let bytes = path.as_bytes();
+    bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/')
+}
+
+/// Validate that a path is absolute (see `is_absolute_path` for what counts as absolute
+/// on each platform).
 pub fn validate_absolute_path(path: &str) -> Result<(), ValidationError> {
-    if !path.starts_with('/') {
+    if !is_absolute_path(path) {
         return Err(ValidationError::RelativeWorkingDir(path.to_string()));
     }
     Ok(())
@@ -189,33 +340,141 @@ pub fn validate_env_var(name: &str, value: &str) -> Result<(), ValidationError>
     Ok(())
 }
 
-/// Internal implementation for testability - takes blocked_paths as parameter.
-/// Resolves a path and checks if it matches or is under any blocked path.
-fn find_blocked_path_impl(path: &str, blocked_paths: &[String]) -> Option<String> {
-    // Resolve the path to get absolute path for comparison
-    let resolved_path = if path.starts_with('/') {
-        Path::new(path).to_path_buf()
+/// Expand a leading `~` in `path` to a home directory, modeled on nu-path's
+/// `expand_tilde`: a bare `~` or `~/...` expands to the current user's home (via the
+/// `HOME` environment variable), and `~name` or `~name/...` expands to `name`'s home via
+/// the passwd database. Paths without a leading `~`, or a `~name` for an unknown user,
+/// are returned unchanged. Expansion runs before absolutization/normalization so the
+/// resulting path is checked against `BLOCKED_PATHS` like any other absolute path.
+fn expand_tilde(path: &str) -> String {
+    if path == "~" {
+        return home_dir_for_current_user().unwrap_or_else(|| path.to_string());
+    }
+    if let Some(rest) = path.strip_prefix("~/") {
+        return match home_dir_for_current_user() {
+            Some(home) => format!("{}/{}", home, rest),
+            None => path.to_string(),
+        };
+    }
+    if let Some(rest) = path.strip_prefix('~') {
+        let (name, tail) = match rest.split_once('/') {
+            Some((name, tail)) => (name, Some(tail)),
+            None => (rest, None),
+        };
+        if !name.is_empty() {
+            if let Some(home) = home_dir_for_user(name) {
+                return match tail {
+                    Some(tail) => format!("{}/{}", home, tail),
+                    None => home,
+                };
+            }
+        }
+    }
+    path.to_string()
+}
+
+/// The current user's home directory, via the `HOME` environment variable.
+fn home_dir_for_current_user() -> Option<String> {
+    std::env::var("HOME").ok()
+}
+
+/// `name`'s home directory, looked up from `/etc/passwd`.
+fn home_dir_for_user(name: &str) -> Option<String> {
+    let contents = std::fs::read_to_string("/etc/passwd").ok()?;
+    contents.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() >= 6 && fields[0] == name {
+            Some(fields[5].to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Logically normalize `path`'s components without touching the filesystem: drop `.`
+/// segments and pop the previous normal component for `..`, never popping past the root.
+/// Unlike `Path::canonicalize()`, this works even when the path (or its tail) doesn't
+/// exist yet, which is exactly the case where `..` could otherwise be used to dodge a
+/// blocked-prefix check.
+pub(crate) fn normalize_components(path: &Path) -> PathBuf {
+    let mut stack: Vec<std::path::Component> = Vec::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => match stack.last() {
+                Some(std::path::Component::Normal(_)) => {
+                    stack.pop();
+                }
+                Some(std::path::Component::RootDir) | None => {}
+                _ => stack.push(component),
+            },
+            other => stack.push(other),
+        }
+    }
+    stack.iter().collect()
+}
+
+/// Make `path` absolute (relative to `working_dir`, or the process's current directory if
+/// `working_dir` is `None`) and logically normalize it, without touching the filesystem.
+/// A leading `~` is expanded to a home directory first (see `expand_tilde`).
+fn absolutize_without_fs(path: &str, working_dir: Option<&str>) -> PathBuf {
+    let expanded = expand_tilde(path);
+    let raw = Path::new(&expanded);
+    let absolute = if raw.is_absolute() {
+        raw.to_path_buf()
+    } else if let Some(dir) = working_dir {
+        Path::new(dir).join(raw)
     } else {
         match std::env::current_dir() {
-            Ok(cwd) => cwd.join(path),
-            Err(_) => return None, // Can't resolve, let the command fail naturally
+            Ok(cwd) => cwd.join(raw),
+            Err(_) => raw.to_path_buf(),
         }
     };
+    normalize_components(&absolute)
+}
 
-    // Try to canonicalize to resolve symlinks (.. is already blocked by validate_no_traversal)
-    let canonical_path = match resolved_path.canonicalize() {
-        Ok(p) => p,
-        Err(_) => resolved_path, // Path might not exist yet, use as-is
-    };
-
-    // Check if path is or is under any blocked path
-    let path_str = canonical_path.to_string_lossy();
-    for blocked in blocked_paths {
-        if path_str == *blocked || path_str.starts_with(&format!("{}/", blocked)) {
-            return Some(blocked.clone());
+/// Resolve `path` to its canonical (symlink-resolved) form for blocked-path comparison,
+/// without requiring the path to exist. Following nushell's nu-path approach: logically
+/// normalize first (see `absolutize_without_fs`), then canonicalize the longest existing
+/// ancestor and re-append the remaining, not-yet-existing tail. This keeps a
+/// not-yet-existing path under a symlinked blocked directory (e.g. `/tmp` -> `/private/tmp`
+/// on macOS) comparable against the canonicalized blocked prefix, instead of silently
+/// falling back to the un-resolved form the moment `canonicalize()` fails.
+fn resolve_for_comparison(path: &str, working_dir: Option<&str>) -> PathBuf {
+    let normalized = absolutize_without_fs(path, working_dir);
+    let components: Vec<_> = normalized.components().collect();
+
+    for split in (1..=components.len()).rev() {
+        let ancestor: PathBuf = components[..split].iter().collect();
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let mut resolved = canonical;
+            for component in &components[split..] {
+                resolved.push(component.as_os_str());
+            }
+            return resolved;
         }
     }
-    None
+
+    normalized
+}
+
+/// Normalize path separators to `/` before blocked-path comparison, so entries like
+/// "/home/user/.ssh" match regardless of whether the resolved path used `\` (Windows)
+/// or `/` (Unix).
+fn normalize_separators_for_comparison(path: &std::path::Path) -> String {
+    let path_str = path.to_string_lossy();
+    if cfg!(windows) {
+        path_str.replace('\\', "/")
+    } else {
+        path_str.into_owned()
+    }
+}
+
+/// Internal implementation for testability - takes a compiled matcher as parameter.
+/// Resolves a path and checks if it matches or is under any blocked path/pattern.
+fn find_blocked_path_impl(path: &str, blocked_paths: &PathMatcher) -> Option<String> {
+    let canonical_path = resolve_for_comparison(path, None);
+    blocked_paths.matching(&normalize_separators_for_comparison(&canonical_path))
 }
 
 /// Resolve a path and check if it matches or is under any blocked path.
@@ -224,46 +483,178 @@ fn find_blocked_path(path: &str) -> Option<String> {
     find_blocked_path_impl(path, &BLOCKED_PATHS)
 }
 
-/// Validate that a path is not blocked
+/// Whether `path` is permitted under the ALLOWED_PATHS allowlist. Allowlist mode is
+/// only active when ALLOWED_PATHS is non-empty; an empty allowlist imposes no
+/// restriction (the deployment is relying on BLOCKED_PATHS instead).
+fn is_allowed_path(path: &str) -> bool {
+    if ALLOWED_PATHS.is_empty() {
+        return true;
+    }
+    let canonical_path = resolve_for_comparison(path, None);
+    ALLOWED_PATHS
+        .matching(&normalize_separators_for_comparison(&canonical_path))
+        .is_some()
+}
+
+/// Validate that a path is not blocked, and - if ALLOWED_PATHS is configured - that it
+/// falls under one of the allowed roots.
 pub fn validate_path(path: &str) -> Result<(), ValidationError> {
     if let Some(blocked) = find_blocked_path(path) {
         return Err(ValidationError::BlockedPath(blocked));
     }
+    if !is_allowed_path(path) {
+        return Err(ValidationError::NotAllowed(path.to_string()));
+    }
+    Ok(())
+}
+
+/// Validate that a path is not blocked by a caller-supplied list of prefixes/patterns,
+/// such as a per-tool policy's `blocked_paths` (in addition to the deployment-wide
+/// `BLOCKED_PATHS` environment variable checked by `validate_path`).
+pub fn validate_path_against(path: &str, blocked_paths: &[String]) -> Result<(), ValidationError> {
+    let matcher = PathMatcher::compile(blocked_paths);
+    if let Some(blocked) = find_blocked_path_impl(path, &matcher) {
+        return Err(ValidationError::BlockedPath(blocked));
+    }
     Ok(())
 }
 
-/// Internal implementation for testability - takes blocked_paths as parameter.
-fn validate_path_with_working_dir_impl(path: &str, working_dir: &str, blocked_paths: &[String]) -> Result<(), ValidationError> {
-    if !working_dir.starts_with('/') {
+/// Internal implementation for testability - takes a compiled matcher as parameter.
+fn validate_path_with_working_dir_impl(path: &str, working_dir: &str, blocked_paths: &PathMatcher) -> Result<(), ValidationError> {
+    if !is_absolute_path(working_dir) {
         return Err(ValidationError::RelativeWorkingDir(working_dir.to_string()));
     }
 
-    let resolved = if path.starts_with('/') {
-        Path::new(path).to_path_buf()
-    } else {
-        Path::new(working_dir).join(path)
-    };
+    let canonical = resolve_for_comparison(path, Some(working_dir));
 
-    // Canonicalize to resolve any remaining path components
-    let canonical = match resolved.canonicalize() {
-        Ok(p) => p,
-        Err(_) => resolved, // Path might not exist, use as-is
-    };
+    if let Some(blocked) = blocked_paths.matching(&normalize_separators_for_comparison(&canonical)) {
+        return Err(ValidationError::BlockedPath(blocked));
+    }
+    Ok(())
+}
+
+/// The name of a per-directory blocklist file, discovered by walking upward from a
+/// working directory, gitignore-style.
+const BLOCKLIST_FILE_NAME: &str = ".command-runner-blocklist";
+
+/// One gitignore-style rule parsed from a blocklist file: whether it negates (re-allows)
+/// a match, and the compiled glob pattern, anchored to the directory the rule's file
+/// lives in.
+struct BlocklistRule {
+    negate: bool,
+    pattern: glob::Pattern,
+}
+
+/// One discovered blocklist file's parsed rules, in file order (gitignore evaluates
+/// rules within a file top-to-bottom, last match wins).
+struct BlocklistLayer {
+    rules: Vec<BlocklistRule>,
+}
 
-    let path_str = canonical.to_string_lossy();
-    for blocked in blocked_paths {
-        if path_str == *blocked || path_str.starts_with(&format!("{}/", blocked)) {
-            return Err(ValidationError::BlockedPath(blocked.clone()));
+/// Parse a blocklist file's contents, anchoring relative patterns to `anchor_dir` (the
+/// directory the file was found in) the way gitignore anchors patterns to the
+/// `.gitignore`'s own directory. Lines starting with `#` are comments; a leading `!`
+/// negates (re-allows) a pattern. Malformed patterns are skipped rather than failing
+/// the whole layer.
+fn parse_blocklist_file(anchor_dir: &Path, contents: &str) -> BlocklistLayer {
+    let anchor = anchor_dir.to_string_lossy();
+    let rules = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (negate, raw_pattern) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let anchored = if raw_pattern.starts_with('/') {
+                format!("{}{}", anchor, raw_pattern)
+            } else {
+                format!("{}/**/{}", anchor, raw_pattern)
+            };
+            glob::Pattern::new(&anchored)
+                .ok()
+                .map(|pattern| BlocklistRule { negate, pattern })
+        })
+        .collect();
+    BlocklistLayer { rules }
+}
+
+/// Walk upward from `start_dir` to the filesystem root, collecting every
+/// `.command-runner-blocklist` file found along the way, ordered from the topmost
+/// ancestor down to `start_dir` itself - so a layer found deeper (closer to
+/// `start_dir`) is evaluated after, and therefore can override, one found higher up.
+fn discover_blocklist_layers(start_dir: &Path) -> Vec<BlocklistLayer> {
+    let mut ancestors = Vec::new();
+    let mut current = Some(start_dir.to_path_buf());
+    while let Some(dir) = current {
+        current = dir.parent().map(Path::to_path_buf);
+        ancestors.push(dir);
+    }
+    ancestors.reverse();
+
+    ancestors
+        .into_iter()
+        .filter_map(|dir| {
+            let contents = std::fs::read_to_string(dir.join(BLOCKLIST_FILE_NAME)).ok()?;
+            Some(parse_blocklist_file(&dir, &contents))
+        })
+        .collect()
+}
+
+/// Resolve the blocked/allowed verdict for `path_str` against the global `blocked_paths`
+/// matcher (treated as the outermost, least specific layer) and the discovered
+/// `.command-runner-blocklist` layers, in order from outermost to nearest. Within and
+/// across layers, the last matching rule wins - mirroring gitignore's evaluation order -
+/// so a `!` negation in a directory closer to the path overrides a block from a
+/// directory further up, or from `BLOCKED_PATHS` itself.
+fn layered_block_verdict(path_str: &str, blocked_paths: &PathMatcher, layers: &[BlocklistLayer]) -> Option<String> {
+    let mut verdict = blocked_paths.matching(path_str);
+
+    for layer in layers {
+        for rule in &layer.rules {
+            if rule.pattern.matches(path_str) {
+                verdict = if rule.negate {
+                    None
+                } else {
+                    Some(rule.pattern.as_str().to_string())
+                };
+            }
         }
     }
+
+    verdict
+}
+
+/// Internal implementation for testability - takes the matcher and pre-parsed layers as
+/// parameters instead of discovering/loading them from disk.
+fn validate_path_layered_impl(
+    path: &str,
+    working_dir: &str,
+    blocked_paths: &PathMatcher,
+    layers: &[BlocklistLayer],
+) -> Result<(), ValidationError> {
+    if !is_absolute_path(working_dir) {
+        return Err(ValidationError::RelativeWorkingDir(working_dir.to_string()));
+    }
+
+    let canonical = resolve_for_comparison(path, Some(working_dir));
+    let path_str = normalize_separators_for_comparison(&canonical);
+
+    if let Some(blocked) = layered_block_verdict(&path_str, blocked_paths, layers) {
+        return Err(ValidationError::BlockedPath(blocked));
+    }
     Ok(())
 }
 
-/// Validate that a path resolved against a working directory is not blocked.
-/// This handles the case where a relative path combined with working_dir could
-/// access a blocked location.
-pub fn validate_path_with_working_dir(path: &str, working_dir: &str) -> Result<(), ValidationError> {
-    validate_path_with_working_dir_impl(path, working_dir, &BLOCKED_PATHS)
+/// Validate `path` (resolved against `working_dir`) against the global `BLOCKED_PATHS`
+/// layered with any `.command-runner-blocklist` files discovered by walking upward from
+/// `working_dir` to the filesystem root, gitignore-style. A `!`-prefixed pattern in a
+/// file closer to `working_dir` can re-allow a path blocked by a file further up, or by
+/// `BLOCKED_PATHS` itself.
+pub fn validate_path_layered(path: &str, working_dir: &str) -> Result<(), ValidationError> {
+    let layers = discover_blocklist_layers(Path::new(working_dir));
+    validate_path_layered_impl(path, working_dir, &BLOCKED_PATHS, &layers)
 }
 
 #[cfg(test)]
@@ -306,7 +697,7 @@ mod tests {
     fn test_find_blocked_path_blocks_exact() {
         let blocked = vec!["/blocked".to_string()];
         assert_eq!(
-            find_blocked_path_impl("/blocked", &blocked),
+            find_blocked_path_impl("/blocked", &PathMatcher::compile(&blocked)),
             Some("/blocked".to_string())
         );
     }
@@ -315,7 +706,7 @@ mod tests {
     fn test_find_blocked_path_blocks_subpath() {
         let blocked = vec!["/blocked".to_string()];
         assert_eq!(
-            find_blocked_path_impl("/blocked/subdir", &blocked),
+            find_blocked_path_impl("/blocked/subdir", &PathMatcher::compile(&blocked)),
             Some("/blocked".to_string())
         );
     }
@@ -324,7 +715,7 @@ mod tests {
     fn test_find_blocked_path_blocks_also_blocked_exact() {
         let blocked = vec!["/also-blocked".to_string()];
         assert_eq!(
-            find_blocked_path_impl("/also-blocked", &blocked),
+            find_blocked_path_impl("/also-blocked", &PathMatcher::compile(&blocked)),
             Some("/also-blocked".to_string())
         );
     }
@@ -333,23 +724,90 @@ mod tests {
     fn test_find_blocked_path_blocks_also_blocked_subpath() {
         let blocked = vec!["/also-blocked".to_string()];
         assert_eq!(
-            find_blocked_path_impl("/also-blocked/subdir", &blocked),
+            find_blocked_path_impl("/also-blocked/subdir", &PathMatcher::compile(&blocked)),
             Some("/also-blocked".to_string())
         );
     }
 
+    #[test]
+    fn test_find_blocked_path_matches_glob_suffix() {
+        let blocked = vec!["*.pem".to_string()];
+        assert_eq!(
+            find_blocked_path_impl("/home/user/certs/server.pem", &PathMatcher::compile(&blocked)),
+            Some("*.pem".to_string())
+        );
+        assert!(find_blocked_path_impl("/home/user/certs/server.crt", &PathMatcher::compile(&blocked)).is_none());
+    }
+
+    #[test]
+    fn test_find_blocked_path_matches_double_star_glob() {
+        let blocked = vec!["**/.ssh".to_string()];
+        assert_eq!(
+            find_blocked_path_impl("/home/user/.ssh", &PathMatcher::compile(&blocked)),
+            Some("**/.ssh".to_string())
+        );
+    }
+
+    #[test]
+    fn test_path_matcher_falls_back_to_prefix_on_invalid_glob() {
+        let patterns = vec!["/blocked[".to_string()];
+        let matcher = PathMatcher::compile(&patterns);
+        assert_eq!(matcher.matching("/blocked["), Some("/blocked[".to_string()));
+    }
+
+    #[test]
+    fn test_path_matcher_is_empty() {
+        assert!(PathMatcher::compile(&[]).is_empty());
+        assert!(!PathMatcher::compile(&["/blocked".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_is_allowed_path_with_empty_allowlist_allows_everything() {
+        assert!(is_allowed_path("/anything/at/all"));
+    }
+
     #[test]
     fn test_validate_path_ok_for_allowed() {
         let temp_dir = tempfile::TempDir::new().unwrap();
         assert!(validate_path(temp_dir.path().to_str().unwrap()).is_ok());
     }
 
+    #[test]
+    fn test_validate_path_against_uses_caller_supplied_list() {
+        let blocked = vec!["/policy-blocked".to_string()];
+        assert!(matches!(
+            validate_path_against("/policy-blocked/secret", &blocked),
+            Err(ValidationError::BlockedPath(_))
+        ));
+        assert!(validate_path_against("/elsewhere", &blocked).is_ok());
+    }
+
     // Null byte detection
     #[test]
     fn test_contains_shell_injection_detects_null_byte() {
         assert!(contains_shell_injection("file\0.txt"));
     }
 
+    // Glob pattern tests
+    #[test]
+    fn test_validate_glob_pattern_allows_glob_chars() {
+        assert!(validate_glob_pattern("src/**/*.rs").is_ok());
+        assert!(validate_glob_pattern("**/Cargo.toml").is_ok());
+        assert!(validate_glob_pattern("file[12].txt").is_ok());
+    }
+
+    #[test]
+    fn test_validate_glob_pattern_still_blocks_other_injection() {
+        assert!(matches!(
+            validate_glob_pattern("*.rs; rm -rf /"),
+            Err(ValidationError::ShellInjection(_))
+        ));
+        assert!(matches!(
+            validate_glob_pattern("$(whoami)*.rs"),
+            Err(ValidationError::ShellInjection(_))
+        ));
+    }
+
     // Flag injection tests
     #[test]
     fn test_is_flag_like_detects_single_dash() {
@@ -513,6 +971,42 @@ mod tests {
         assert!(validate_absolute_path("/home/user/dir").is_ok());
     }
 
+    #[test]
+    fn test_contains_traversal_is_component_based_not_substring() {
+        // A filename containing ".." shouldn't be mistaken for a traversal attempt.
+        assert!(!contains_traversal("my..file"));
+        assert!(!contains_traversal("/tmp/my..file"));
+        assert!(!contains_traversal("..file/safe"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_contains_traversal_splits_on_backslash_on_windows() {
+        assert!(contains_traversal(r"..\Windows"));
+        assert!(contains_traversal(r"C:\work\..\..\Windows"));
+        assert!(!contains_traversal(r"C:\work\my..file"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_validate_absolute_path_allows_windows_forms() {
+        assert!(validate_absolute_path(r"C:\work").is_ok());
+        assert!(validate_absolute_path(r"C:/work").is_ok());
+        assert!(validate_absolute_path(r"\\?\C:\work").is_ok());
+        assert!(validate_absolute_path(r"\\server\share").is_ok());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_validate_absolute_path_rejects_drive_relative() {
+        // "C:work" (no separator after the drive letter) is drive-relative, not
+        // absolute, and should still be rejected.
+        assert!(matches!(
+            validate_absolute_path("C:work"),
+            Err(ValidationError::RelativeWorkingDir(_))
+        ));
+    }
+
     // Blocked path tests using temp directories
     #[test]
     fn test_blocked_path_with_temp_dir() {
@@ -524,7 +1018,7 @@ mod tests {
 
         // Exact match should be blocked
         assert_eq!(
-            find_blocked_path_impl(&blocked_path_str, &blocked),
+            find_blocked_path_impl(&blocked_path_str, &PathMatcher::compile(&blocked)),
             Some(blocked_path_str.clone())
         );
     }
@@ -539,7 +1033,7 @@ mod tests {
         // Subpath should be blocked (non-existent subpath is resolved relative to parent)
         let subpath = format!("{}/subdir/file.txt", blocked_path_str);
         assert_eq!(
-            find_blocked_path_impl(&subpath, &blocked),
+            find_blocked_path_impl(&subpath, &PathMatcher::compile(&blocked)),
             Some(blocked_path_str)
         );
     }
@@ -552,7 +1046,7 @@ mod tests {
         let blocked = vec!["/some/other/path".to_string()];
 
         // Should not be blocked when not in list
-        assert!(find_blocked_path_impl(&safe_path_str, &blocked).is_none());
+        assert!(find_blocked_path_impl(&safe_path_str, &PathMatcher::compile(&blocked)).is_none());
     }
 
     #[test]
@@ -570,11 +1064,106 @@ mod tests {
         // Following symlink should detect blocked path
         let link_path_str = link_path.to_string_lossy().to_string();
         assert_eq!(
-            find_blocked_path_impl(&link_path_str, &blocked),
+            find_blocked_path_impl(&link_path_str, &PathMatcher::compile(&blocked)),
+            Some(blocked_path_str)
+        );
+    }
+
+    #[test]
+    fn test_blocked_path_with_symlink_nonexistent_tail() {
+        // A path that doesn't exist yet, nested under a symlinked blocked directory,
+        // must still be caught - this is the bypass the canonicalize()-with-fallback
+        // logic used to miss, since canonicalize() fails outright on a missing path.
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let blocked_path = temp_dir.path().canonicalize().unwrap();
+        let blocked_path_str = blocked_path.to_string_lossy().to_string();
+        let blocked = vec![blocked_path_str.clone()];
+
+        let link_dir = tempfile::TempDir::new().unwrap();
+        let link_path = link_dir.path().join("link");
+        std::os::unix::fs::symlink(temp_dir.path(), &link_path).unwrap();
+
+        let nonexistent = link_path.join("not-yet-created/nested.txt");
+        let nonexistent_str = nonexistent.to_string_lossy().to_string();
+        assert_eq!(
+            find_blocked_path_impl(&nonexistent_str, &PathMatcher::compile(&blocked)),
             Some(blocked_path_str)
         );
     }
 
+    #[test]
+    fn test_normalize_components_collapses_parent_dir_without_escaping_root() {
+        assert_eq!(
+            normalize_components(Path::new("/a/b/../c")),
+            Path::new("/a/c")
+        );
+        assert_eq!(
+            normalize_components(Path::new("/a/../../b")),
+            Path::new("/b")
+        );
+        assert_eq!(
+            normalize_components(Path::new("/a/./b/./c")),
+            Path::new("/a/b/c")
+        );
+    }
+
+    #[test]
+    fn test_resolve_for_comparison_normalizes_traversal_before_canonicalizing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let canonical_root = temp_dir.path().canonicalize().unwrap();
+        let sneaky = format!(
+            "{}/subdir/../nonexistent/child",
+            canonical_root.to_string_lossy()
+        );
+        let resolved = resolve_for_comparison(&sneaky, None);
+        assert_eq!(
+            resolved,
+            canonical_root.join("nonexistent").join("child")
+        );
+    }
+
+    #[test]
+    fn test_expand_tilde_bare() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(expand_tilde("~"), home);
+    }
+
+    #[test]
+    fn test_expand_tilde_with_trailing_path() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(expand_tilde("~/.ssh/id_rsa"), format!("{}/.ssh/id_rsa", home));
+    }
+
+    #[test]
+    fn test_expand_tilde_other_user() {
+        // root always exists in /etc/passwd with a known home directory.
+        assert_eq!(expand_tilde("~root/.bashrc"), "/root/.bashrc");
+    }
+
+    #[test]
+    fn test_expand_tilde_unknown_user_left_unchanged() {
+        assert_eq!(
+            expand_tilde("~this-user-should-not-exist/.bashrc"),
+            "~this-user-should-not-exist/.bashrc"
+        );
+    }
+
+    #[test]
+    fn test_expand_tilde_leaves_non_tilde_paths_unchanged() {
+        assert_eq!(expand_tilde("/tmp/foo"), "/tmp/foo");
+        assert_eq!(expand_tilde("relative/path"), "relative/path");
+    }
+
+    #[test]
+    fn test_find_blocked_path_catches_tilde_expansion() {
+        let home = std::env::var("HOME").unwrap();
+        let blocked = vec![format!("{}/.ssh", home)];
+        assert_eq!(
+            find_blocked_path_impl("~/.ssh/id_rsa", &PathMatcher::compile(&blocked)),
+            Some(format!("{}/.ssh", home))
+        );
+    }
+
     // Tests for validate_path_with_working_dir_impl
     #[test]
     fn test_validate_path_with_working_dir_blocks_relative_path_to_blocked() {
@@ -591,7 +1180,7 @@ mod tests {
 
         // Relative path "blocked" from working_dir should be blocked
         assert!(matches!(
-            validate_path_with_working_dir_impl("blocked", &working_dir_str, &blocked),
+            validate_path_with_working_dir_impl("blocked", &working_dir_str, &PathMatcher::compile(&blocked)),
             Err(ValidationError::BlockedPath(_))
         ));
     }
@@ -608,14 +1197,14 @@ mod tests {
         let working_dir_str = working_dir.to_string_lossy().to_string();
 
         // Relative path "safe" from working_dir should be allowed
-        assert!(validate_path_with_working_dir_impl("safe", &working_dir_str, &blocked).is_ok());
+        assert!(validate_path_with_working_dir_impl("safe", &working_dir_str, &PathMatcher::compile(&blocked)).is_ok());
     }
 
     #[test]
     fn test_validate_path_with_working_dir_rejects_relative_working_dir() {
         let blocked = vec![];
         assert!(matches!(
-            validate_path_with_working_dir_impl(".", "relative/dir", &blocked),
+            validate_path_with_working_dir_impl(".", "relative/dir", &PathMatcher::compile(&blocked)),
             Err(ValidationError::RelativeWorkingDir(_))
         ));
     }
@@ -632,8 +1221,84 @@ mod tests {
 
         // Absolute path should be checked directly, ignoring working_dir
         assert!(matches!(
-            validate_path_with_working_dir_impl(&blocked_path_str, "/some/other/dir", &blocked),
+            validate_path_with_working_dir_impl(&blocked_path_str, "/some/other/dir", &PathMatcher::compile(&blocked)),
+            Err(ValidationError::BlockedPath(_))
+        ));
+    }
+
+    // Tests for the layered, file-based blocklist (validate_path_layered)
+    #[test]
+    fn test_parse_blocklist_file_blocks_anchored_relative_pattern() {
+        let anchor = tempfile::TempDir::new().unwrap();
+        let layer = parse_blocklist_file(anchor.path(), "secrets/*.pem\n");
+        let target = format!("{}/secrets/server.pem", anchor.path().to_string_lossy());
+        assert!(layer.rules[0].pattern.matches(&target));
+    }
+
+    #[test]
+    fn test_parse_blocklist_file_skips_comments_and_blank_lines() {
+        let anchor = tempfile::TempDir::new().unwrap();
+        let layer = parse_blocklist_file(anchor.path(), "# a comment\n\n*.pem\n");
+        assert_eq!(layer.rules.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_blocklist_file_parses_negation() {
+        let anchor = tempfile::TempDir::new().unwrap();
+        let layer = parse_blocklist_file(anchor.path(), "!allowed.pem\n");
+        assert!(layer.rules[0].negate);
+    }
+
+    #[test]
+    fn test_layered_block_verdict_nearest_negation_overrides_global_block() {
+        let anchor = tempfile::TempDir::new().unwrap();
+        let target = format!("{}/allowed.pem", anchor.path().to_string_lossy());
+        let blocked = PathMatcher::compile(&["*.pem".to_string()]);
+        let layers = vec![parse_blocklist_file(anchor.path(), "!allowed.pem\n")];
+
+        assert_eq!(layered_block_verdict(&target, &blocked, &layers), None);
+    }
+
+    #[test]
+    fn test_layered_block_verdict_nearer_layer_overrides_farther_one() {
+        let root = tempfile::TempDir::new().unwrap();
+        let sub = root.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let target = format!("{}/secret.pem", sub.to_string_lossy());
+
+        let blocked = PathMatcher::compile(&[]);
+        let layers = vec![
+            parse_blocklist_file(root.path(), "*.pem\n"),
+            parse_blocklist_file(&sub, "!secret.pem\n"),
+        ];
+
+        assert_eq!(layered_block_verdict(&target, &blocked, &layers), None);
+    }
+
+    #[test]
+    fn test_discover_blocklist_layers_finds_file_in_ancestor() {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::write(root.path().join(BLOCKLIST_FILE_NAME), "*.pem\n").unwrap();
+        let sub = root.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+
+        let layers = discover_blocklist_layers(&sub);
+        assert!(layers.iter().any(|layer| !layer.rules.is_empty()));
+    }
+
+    #[test]
+    fn test_validate_path_layered_impl_blocks_via_global_and_respects_negation() {
+        let root = tempfile::TempDir::new().unwrap();
+        let canonical_root = root.path().canonicalize().unwrap();
+        let root_str = canonical_root.to_string_lossy().to_string();
+
+        let blocked = PathMatcher::compile(&["*.pem".to_string()]);
+        let layers = vec![parse_blocklist_file(&canonical_root, "!allowed.pem\n")];
+
+        assert!(matches!(
+            validate_path_layered_impl("server.pem", &root_str, &blocked, &layers),
             Err(ValidationError::BlockedPath(_))
         ));
+        assert!(validate_path_layered_impl("allowed.pem", &root_str, &blocked, &layers).is_ok());
     }
 }
@@ -6,7 +6,7 @@ use rmcp::{
 
 use crate::request::ToolRequest;
 use crate::security::Validatable;
-use crate::tools::{git, ls, GitRequest, LsRequest};
+use crate::tools::{git, ls, test, GitRequest, LsRequest, TestRequest};
 
 #[derive(Clone)]
 pub struct CommandRunnerServer {
@@ -28,32 +28,92 @@ impl Default for CommandRunnerServer {
 }
 
 use crate::request::ExecutionContext;
+use std::time::Duration;
+
+/// Upper bound on how long a single watch-mode tool call may run before returning,
+/// regardless of `watch_ms`/iteration count. Tool calls here are synchronous
+/// request/response, so a watch session can't stream incremental updates yet; it
+/// accumulates a transcript of re-runs and returns it in one shot once the session ends.
+const WATCH_DEADLINE: Duration = Duration::from_secs(600);
 
 /// Execute a tool request, validating it first and applying output transformations.
 /// This enforces at compile time that all requests must implement Validatable.
-fn run_tool<R: Validatable>(
+///
+/// `tool_name` identifies the tool for cache-key purposes (e.g. "ls_tool", "git") so two
+/// tools given the same inner request don't collide. The raw command output is cached
+/// before `transform_output` runs, so different grep/head/tail parameters on an otherwise
+/// identical invocation can reuse one execution.
+fn run_tool<R: Validatable + serde::Serialize>(
+    tool_name: &str,
     req: &ToolRequest<R>,
-    execute: impl FnOnce(&R, &ExecutionContext) -> String,
+    execute: impl Fn(&R, &ExecutionContext) -> String,
 ) -> String {
     if let Err(e) = req.validate() {
         return e.to_string();
     }
     let ctx = req.execution_context();
-    let output = execute(&req.inner, &ctx);
-    req.transform_output(output)
+
+    let cache_key = req
+        .cache_enabled()
+        .then(|| crate::cache::cache_key(tool_name, &req.inner, &ctx))
+        .flatten();
+
+    let raw_output = match cache_key.as_deref().and_then(crate::cache::get) {
+        Some(cached) => cached,
+        None => {
+            let output = execute(&req.inner, &ctx);
+            if let Some(key) = &cache_key {
+                crate::cache::put(key, &output, req.cache_ttl());
+            }
+            output
+        }
+    };
+
+    let initial = req.transform_output(raw_output);
+
+    if req.watch.unwrap_or(false) {
+        return run_watched(req, &ctx, execute, initial);
+    }
+
+    initial
+}
+
+/// Re-run `execute` whenever `req.watch_paths()` change, appending each debounced
+/// re-run's transformed output to a running transcript until the watch session ends.
+fn run_watched<R: Validatable>(
+    req: &ToolRequest<R>,
+    ctx: &ExecutionContext,
+    execute: impl Fn(&R, &ExecutionContext) -> String,
+    initial: String,
+) -> String {
+    let mut transcript = vec![format!("--- initial ---\n{}", initial)];
+    let paths = req.watch_paths();
+    let debounce = req.watch_debounce();
+
+    crate::watch::watch_and_rerun(&paths, debounce, Some(WATCH_DEADLINE), |iteration| {
+        let output = req.transform_output(execute(&req.inner, ctx));
+        transcript.push(format!("--- change {} ---\n{}", iteration, output));
+        true
+    });
+
+    transcript.join("\n\n")
 }
 
-const SERVER_INSTRUCTIONS: &str = r#"A command runner MCP server that provides ls_tool for listing directory contents and git for running git commands.
+const SERVER_INSTRUCTIONS: &str = r#"A command runner MCP server that provides ls_tool for listing directory contents, git for running git commands, and test for running a project's test suite.
 
 All tools support these optional parameters:
 - grep_pattern: regex to filter lines (invert_grep: true to exclude matches)
 - head/tail: limit to first/last N lines
-- sort: sort lines alphabetically
-- unique: remove consecutive duplicate lines
+- sort/numeric_sort: sort lines alphabetically or numerically (reverse: true to flip either)
+- unique/unique_global: remove consecutive or non-adjacent duplicate lines
+- replace_pattern/replace_with: regex substitution per line (supports $1-style backreferences)
+- cut_delim/cut_fields: select delimiter-separated fields, like `awk '{print $1,$3}'`
 - timeout_ms: command timeout in milliseconds
 - working_dir: directory to run command in
 - env: environment variables as {"KEY": "value"}
 - transform_order: array specifying order of transformations ["grep", "sort", "unique", "head", "tail"]
+- cache/cache_ttl_ms: reuse a cached result for an identical prior invocation (same tool, request, working_dir, env) instead of re-executing; set cache: false to bypass
+- watch: re-run the command on filesystem changes under watch_paths (default: working_dir), returning a transcript of each re-run; watch_paths/watch_ms tune what's watched and the debounce window
 
 Default transform order: grep -> sort -> unique -> head -> tail"#;
 
@@ -70,7 +130,7 @@ Supports output transformations:
 
 Example - list only .rs files, sorted: {\"path\": \"src\", \"grep_pattern\": \"\\\\.rs$\", \"sort\": true}")]
     fn ls_tool(&self, Parameters(req): Parameters<ToolRequest<LsRequest>>) -> String {
-        run_tool(&req, ls::execute)
+        run_tool("ls_tool", &req, ls::execute)
     }
 
     #[tool(description = "Default/preferred tool for running git commands (status, add, commit, checkout). Use this instead of terminal commands for all git operations.
@@ -84,7 +144,16 @@ Supports output transformations:
 
 Example - show only modified files: {\"subcommand\": \"status\", \"grep_pattern\": \"modified:\"}")]
     fn git(&self, Parameters(req): Parameters<ToolRequest<GitRequest>>) -> String {
-        run_tool(&req, git::execute)
+        run_tool("git", &req, git::execute)
+    }
+
+    #[tool(description = "Run a project's test suite (cargo test, npm test, deno test, pytest, go test) and return a structured pass/fail summary. Use this instead of terminal commands when you need to know whether tests passed, not just scrape their output.
+
+The first line of output is a stable header: `total=N passed=N failed=N ignored=N`, with ` time=Ns` appended when the runner reports a duration, followed by `FAILED: <name>` lines for each failure, then the raw runner output (still subject to grep_pattern/head/tail like any other tool). Counts are parsed from cargo/libtest and jest-style summaries only; for other runners they stay at zero. With structured_output set, the CommandResult JSON is returned instead, without the header.
+
+Example - run a single cargo test by name: {\"runner\": \"cargo\", \"filter\": \"test_name\"}")]
+    fn test(&self, Parameters(req): Parameters<ToolRequest<TestRequest>>) -> String {
+        run_tool("test", &req, test::execute)
     }
 }
 
@@ -1,41 +1,142 @@
 use rmcp::schemars;
-use serde::Deserialize;
-use std::process::Command;
+use serde::{Deserialize, Serialize};
 
-use crate::executor::run_command;
+use crate::executor::{create_command, run_command, run_command_structured, ExecutionResult};
+use crate::policy::policy;
 use crate::request::ExecutionContext;
-use crate::security::{validate_argument, Validatable, ValidationError};
+use crate::security::{validate_argument, validate_no_traversal, validate_path, validate_path_against, validate_path_layered, Validatable, ValidationError};
 
-/// Allowed git subcommands
-const ALLOWED_GIT_SUBCOMMANDS: &[&str] = &["status", "add", "commit", "checkout"];
+/// Per-subcommand argument policy: which flags are permitted, whether free-form pathspec
+/// arguments (e.g. a file path to `git diff`) are allowed at all, and which safe flags are
+/// always appended regardless of what the caller asked for. Subcommands not listed here
+/// fall back to the global shell-injection check only (in the default policy that's
+/// status/add/commit/checkout, none of which take a meaningful
+/// pathspec in the way this tool uses them).
+struct SubcommandArgPolicy {
+    allowed_flags: &'static [&'static str],
+    allow_pathspec: bool,
+    forced_args: &'static [&'static str],
+}
+
+static GIT_SUBCOMMAND_POLICIES: &[(&str, SubcommandArgPolicy)] = &[
+    (
+        "log",
+        SubcommandArgPolicy {
+            allowed_flags: &["--oneline", "--graph", "--stat", "--name-only", "-n", "--since", "--until", "--author"],
+            allow_pathspec: true,
+            forced_args: &["--no-pager", "--no-color"],
+        },
+    ),
+    (
+        "diff",
+        SubcommandArgPolicy {
+            allowed_flags: &["--stat", "--name-only", "--cached", "--staged"],
+            allow_pathspec: true,
+            forced_args: &["--no-pager", "--no-color"],
+        },
+    ),
+    (
+        "show",
+        SubcommandArgPolicy {
+            allowed_flags: &["--stat", "--name-only"],
+            allow_pathspec: true,
+            forced_args: &["--no-pager", "--no-color"],
+        },
+    ),
+    (
+        "branch",
+        SubcommandArgPolicy {
+            allowed_flags: &["-a", "-r", "-v", "--list"],
+            allow_pathspec: false,
+            forced_args: &["--no-color"],
+        },
+    ),
+    (
+        "rev-parse",
+        SubcommandArgPolicy {
+            allowed_flags: &["--abbrev-ref", "--short", "--verify"],
+            allow_pathspec: true,
+            forced_args: &[],
+        },
+    ),
+];
+
+fn subcommand_arg_policy(subcommand: &str) -> Option<&'static SubcommandArgPolicy> {
+    GIT_SUBCOMMAND_POLICIES
+        .iter()
+        .find(|(name, _)| *name == subcommand)
+        .map(|(_, policy)| policy)
+}
 
 /// Request parameters for the git tool
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct GitRequest {
     /// The git subcommand to run (status, add, commit, checkout)
     pub subcommand: String,
     /// Arguments to pass to the git subcommand
     #[serde(default)]
     pub args: Vec<String>,
+    /// When the subcommand is "status", return a structured JSON summary (branch,
+    /// upstream, ahead/behind, and categorized file lists) instead of raw text.
+    #[serde(default)]
+    pub structured: bool,
+    /// Return a structured `CommandResult` (stdout, stderr, exit_code, success) as JSON
+    /// instead of flattening the output to a single string. Ignored when `structured` is
+    /// also set for a "status" call, which returns the `GitStatus` summary instead.
+    #[serde(default)]
+    pub structured_output: bool,
 }
 
 impl Validatable for GitRequest {
     fn validate(&self) -> Result<(), ValidationError> {
-        // Validate subcommand is allowed
-        if !ALLOWED_GIT_SUBCOMMANDS.contains(&self.subcommand.as_str()) {
-            return Err(ValidationError::ShellInjection(format!(
-                "Subcommand '{}' is not allowed. Allowed subcommands: {}",
-                self.subcommand,
-                ALLOWED_GIT_SUBCOMMANDS.join(", ")
-            )));
+        let git_policy = &policy().git;
+
+        // Validate subcommand is allowed per the loaded policy
+        if !git_policy.allowed_subcommands.contains(&self.subcommand) {
+            return Err(git_policy.reject_subcommand(&self.subcommand));
         }
 
         // Check for shell injection in subcommand
         validate_argument(&self.subcommand)?;
 
-        // Check for shell injection in arguments
+        // Check for shell injection in arguments, plus any policy-defined argument patterns
         for arg in &self.args {
             validate_argument(arg)?;
+            if !git_policy.arg_allowed(arg) {
+                return Err(ValidationError::ShellInjection(arg.to_string()));
+            }
+        }
+
+        // Subcommands with a dedicated arg policy (e.g. read-only introspection commands
+        // like log/diff/show) get finer-grained checks: flags must be on the allowlist,
+        // and pathspec arguments go through the usual traversal/blocked-path checks.
+        if let Some(sub_policy) = subcommand_arg_policy(&self.subcommand) {
+            for arg in &self.args {
+                if arg.starts_with('-') {
+                    if !sub_policy.allowed_flags.contains(&arg.as_str()) {
+                        return Err(ValidationError::DisallowedFlag {
+                            flag: arg.clone(),
+                            subcommand: self.subcommand.clone(),
+                            allowed: sub_policy.allowed_flags.join(", "),
+                        });
+                    }
+                } else {
+                    if !sub_policy.allow_pathspec {
+                        return Err(ValidationError::DisallowedFlag {
+                            flag: arg.clone(),
+                            subcommand: self.subcommand.clone(),
+                            allowed: sub_policy.allowed_flags.join(", "),
+                        });
+                    }
+                    // Block ".." the same way ls does, unless the loaded policy
+                    // explicitly permits traversal for this tool.
+                    if !git_policy.allow_traversal {
+                        validate_no_traversal(arg)?;
+                    }
+                    validate_path(arg)?;
+                    validate_path_against(arg, &git_policy.blocked_paths)?;
+                }
+            }
         }
 
         Ok(())
@@ -44,12 +145,155 @@ impl Validatable for GitRequest {
 
 /// Execute a git command with a validated request and execution context
 pub fn execute(req: &GitRequest, ctx: &ExecutionContext) -> String {
-    let mut cmd = Command::new("git");
+    // Validate that pathspec args combined with working_dir don't access blocked paths -
+    // `validate()` only checked them against the process's own cwd, not the working_dir
+    // this request will actually run in. Also honors any `.command-runner-blocklist`
+    // files discovered by walking up from working_dir.
+    if let Some(ref working_dir) = ctx.working_dir {
+        if let Err(e) = validate_path_layered(working_dir, working_dir) {
+            return format!("Error: {}", e);
+        }
+        if let Some(sub_policy) = subcommand_arg_policy(&req.subcommand) {
+            if sub_policy.allow_pathspec {
+                for arg in &req.args {
+                    if !arg.starts_with('-') {
+                        if let Err(e) = validate_path_layered(arg, working_dir) {
+                            return format!("Error: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if req.structured && req.subcommand == "status" {
+        return execute_structured_status(ctx);
+    }
+
+    let mut cmd = match create_command("git") {
+        Ok(cmd) => cmd,
+        Err(e) => return format!("Error: {}", e),
+    };
     cmd.arg(&req.subcommand);
     cmd.args(&req.args);
+    // Never let a read-only introspection command block on a pager/editor/credential prompt.
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(sub_policy) = subcommand_arg_policy(&req.subcommand) {
+        cmd.args(sub_policy.forced_args);
+    }
+
+    if req.structured_output {
+        let result = run_command_structured(cmd, ctx);
+        return serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|e| format!("Error: Failed to serialize command result: {}", e));
+    }
     run_command(cmd, ctx).into_string()
 }
 
+/// Branch/ahead-behind/file-category summary parsed from `git status --porcelain=2`
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub staged: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    pub renamed: Vec<String>,
+    pub untracked: Vec<String>,
+    pub conflicted: Vec<String>,
+}
+
+fn execute_structured_status(ctx: &ExecutionContext) -> String {
+    let mut cmd = match create_command("git") {
+        Ok(cmd) => cmd,
+        Err(e) => return format!("Error: {}", e),
+    };
+    cmd.args(["status", "--porcelain=2", "--branch", "-z"]);
+
+    match run_command(cmd, ctx) {
+        ExecutionResult::Success(raw) => serde_json::to_string_pretty(&parse_porcelain_v2(&raw))
+            .unwrap_or_else(|e| format!("Error: Failed to serialize git status: {}", e)),
+        ExecutionResult::Error(s) => s,
+        ExecutionResult::Timeout => "Error: Command timed out".to_string(),
+        graceful @ ExecutionResult::TimeoutGraceful(_) => graceful.into_string(),
+    }
+}
+
+/// Parse `git status --porcelain=2 --branch -z` output into a `GitStatus`.
+///
+/// Records are NUL-separated (`-z`); a rename/copy record (`2 ...`) is followed by an
+/// extra NUL-terminated field holding the original path. Ordinary changed entries
+/// (`1 `/`2 `) carry a two-character `XY` code: `X` is the staged/index state, `Y` the
+/// worktree state, classified independently so a file can appear in more than one
+/// category. `u ` lines are unmerged/conflicted, `? ` untracked, `! ` ignored (skipped).
+pub fn parse_porcelain_v2(raw: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut tokens = raw.split('\0');
+
+    while let Some(token) = tokens.next() {
+        if token.is_empty() {
+            continue;
+        }
+        if let Some(rest) = token.strip_prefix("# branch.head ") {
+            if rest != "(detached)" {
+                status.branch = Some(rest.to_string());
+            }
+        } else if let Some(rest) = token.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(rest.to_string());
+        } else if let Some(rest) = token.strip_prefix("# branch.ab ") {
+            let mut parts = rest.split_whitespace();
+            status.ahead = parts
+                .next()
+                .and_then(|s| s.strip_prefix('+'))
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            status.behind = parts
+                .next()
+                .and_then(|s| s.strip_prefix('-'))
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+        } else if let Some(rest) = token.strip_prefix("1 ") {
+            classify_ordinary(rest, &mut status);
+        } else if let Some(rest) = token.strip_prefix("2 ") {
+            // The new path ends this record; the original path is the next NUL-terminated field.
+            classify_ordinary(rest, &mut status);
+            tokens.next();
+        } else if let Some(rest) = token.strip_prefix("u ") {
+            if let Some(path) = rest.split_whitespace().last() {
+                status.conflicted.push(path.to_string());
+            }
+        } else if let Some(path) = token.strip_prefix("? ") {
+            status.untracked.push(path.to_string());
+        }
+        // "! " (ignored) entries are intentionally skipped.
+    }
+
+    status
+}
+
+fn classify_ordinary(rest: &str, status: &mut GitStatus) {
+    let mut chars = rest.chars();
+    let x = chars.next().unwrap_or('.');
+    let y = chars.next().unwrap_or('.');
+    let path = match rest.split_whitespace().last() {
+        Some(p) => p.to_string(),
+        None => return,
+    };
+
+    if x == 'R' || x == 'C' {
+        status.renamed.push(path.clone());
+    } else if x != '.' {
+        status.staged.push(path.clone());
+    }
+    match y {
+        'M' => status.modified.push(path),
+        'D' => status.deleted.push(path),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,6 +313,8 @@ mod tests {
         let req = GitRequest {
             subcommand: "status".to_string(),
             args: vec![],
+            structured: false,
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
 
@@ -86,6 +332,8 @@ mod tests {
         let req = GitRequest {
             subcommand: "push".to_string(),
             args: vec![],
+            structured: false,
+            structured_output: false,
         };
         let err = req.validate().unwrap_err();
         assert!(err.to_string().contains("not allowed"));
@@ -96,6 +344,8 @@ mod tests {
         let req = GitRequest {
             subcommand: "status; echo hello".to_string(),
             args: vec![],
+            structured: false,
+            structured_output: false,
         };
         assert!(req.validate().is_err());
     }
@@ -105,6 +355,8 @@ mod tests {
         let req = GitRequest {
             subcommand: "status".to_string(),
             args: vec!["; echo hello".to_string()],
+            structured: false,
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -117,10 +369,236 @@ mod tests {
         let req = GitRequest {
             subcommand: "add".to_string(),
             args: vec!["file.txt | cat /etc/passwd".to_string()],
+            structured: false,
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
             Err(ValidationError::ShellInjection(_))
         ));
     }
+
+    // Structured status parsing tests
+    #[test]
+    fn test_parse_porcelain_v2_branch_and_ahead_behind() {
+        let raw = "# branch.head main\0# branch.upstream origin/main\0# branch.ab +2 -1\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status.branch, Some("main".to_string()));
+        assert_eq!(status.upstream, Some("origin/main".to_string()));
+        assert_eq!(status.ahead, 2);
+        assert_eq!(status.behind, 1);
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_staged_and_modified() {
+        // staged add (X=A) and a separately worktree-modified file (Y=M)
+        let raw = "1 AM N... 100644 100644 100644 0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 src/main.rs\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status.staged, vec!["src/main.rs".to_string()]);
+        assert_eq!(status.modified, vec!["src/main.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_deleted() {
+        let raw = "1 .D N... 100644 100644 000000 0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 gone.txt\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status.deleted, vec!["gone.txt".to_string()]);
+        assert!(status.staged.is_empty());
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_renamed_with_original_path() {
+        let raw = "2 R. N... 100644 100644 100644 0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 R100 new.txt\0old.txt\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status.renamed, vec!["new.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_untracked_and_conflicted() {
+        let raw = "? new_file.txt\0u UU N... 100644 100644 100644 100644 0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 conflict.txt\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status.untracked, vec!["new_file.txt".to_string()]);
+        assert_eq!(status.conflicted, vec!["conflict.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_porcelain_v2_ignored_entries_skipped() {
+        let raw = "! target/\0";
+        let status = parse_porcelain_v2(raw);
+        assert_eq!(status, GitStatus::default());
+    }
+
+    #[test]
+    fn test_execute_structured_output_returns_command_result_json() {
+        let temp_dir = TempDir::new().unwrap();
+        StdCommand::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+
+        let req = GitRequest {
+            subcommand: "status".to_string(),
+            args: vec![],
+            structured: false,
+            structured_output: true,
+        };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["success"], true);
+        assert_eq!(parsed["exit_code"], 0);
+    }
+
+    // Per-subcommand argument policy tests
+    #[test]
+    fn test_validate_allows_known_flag_for_log() {
+        let req = GitRequest {
+            subcommand: "log".to_string(),
+            args: vec!["--oneline".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_flag_for_log() {
+        let req = GitRequest {
+            subcommand: "log".to_string(),
+            args: vec!["--follow".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::DisallowedFlag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_allows_pathspec_for_diff() {
+        let req = GitRequest {
+            subcommand: "diff".to_string(),
+            args: vec!["src/main.rs".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_traversal_pathspec_for_diff() {
+        let req = GitRequest {
+            subcommand: "diff".to_string(),
+            args: vec!["../../etc/passwd".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_pathspec_for_branch() {
+        // `branch` doesn't allow free-form pathspec arguments
+        let req = GitRequest {
+            subcommand: "branch".to_string(),
+            args: vec!["some-file".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::DisallowedFlag { .. })
+        ));
+    }
+
+    #[test]
+    fn test_execute_log_appends_forced_safe_flags() {
+        let req = GitRequest {
+            subcommand: "log".to_string(),
+            args: vec!["--oneline".to_string()],
+            structured: false,
+            structured_output: true,
+        };
+        assert!(req.validate().is_ok());
+        let temp_dir = TempDir::new().unwrap();
+        StdCommand::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        // No commits yet, so `git log` exits non-zero, but it must not hang waiting on a
+        // pager or credential prompt - if forced_args/GIT_TERMINAL_PROMPT weren't applied
+        // this would be the place a hang could slip through unnoticed.
+        let result = execute(&req, &ctx);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(parsed.get("success").is_some());
+    }
+
+    #[test]
+    fn test_execute_allows_relative_pathspec_resolved_against_working_dir() {
+        // `validate()` has no access to `working_dir`, so `execute()` must redo the
+        // working-dir-aware check itself (mirroring `ls::execute`) rather than trusting
+        // that `validate()` already covered it against the right directory.
+        let temp_dir = TempDir::new().unwrap();
+        StdCommand::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+
+        let req = GitRequest {
+            subcommand: "diff".to_string(),
+            args: vec!["src/main.rs".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        assert!(!result.starts_with("Error: Path"));
+    }
+
+    #[test]
+    fn test_execute_rejects_pathspec_blocked_by_layered_blocklist_file() {
+        let temp_dir = TempDir::new().unwrap();
+        StdCommand::new("git")
+            .args(["init"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        std::fs::write(temp_dir.path().join(".command-runner-blocklist"), "secret.txt\n").unwrap();
+
+        let req = GitRequest {
+            subcommand: "diff".to_string(),
+            args: vec!["secret.txt".to_string()],
+            structured: false,
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        assert!(result.starts_with("Error: Reading path"));
+    }
 }
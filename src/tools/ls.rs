@@ -1,48 +1,128 @@
 use rmcp::schemars;
-use serde::Deserialize;
-use std::process::Command;
+use serde::{Deserialize, Serialize};
 
-use crate::executor::run_command;
+use crate::executor::{create_command, run_command, run_command_structured};
+use crate::policy::policy;
 use crate::request::ExecutionContext;
-use crate::security::{validate_argument, validate_no_traversal, validate_not_flag, validate_path, validate_path_with_working_dir, Validatable, ValidationError};
+use crate::security::{validate_argument, validate_glob_pattern, validate_no_traversal, validate_not_flag, validate_path, validate_path_against, validate_path_layered, Validatable, ValidationError};
 
 /// Request parameters for the ls tool
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct LsRequest {
     /// The path to list contents of. Defaults to "." if not provided.
+    /// May also be a glob pattern (e.g. "src/**/*.rs" or "**/Cargo.toml"),
+    /// in which case matched paths are returned instead of a directory listing.
     #[serde(default = "default_path")]
     pub path: String,
+    /// Return a structured `CommandResult` (stdout, stderr, exit_code, success) as JSON
+    /// instead of flattening the output to a single string. Has no effect on glob matches,
+    /// which don't invoke a subprocess.
+    #[serde(default)]
+    pub structured_output: bool,
 }
 
 fn default_path() -> String {
     ".".to_string()
 }
 
+/// A path counts as a glob pattern if it contains any glob metacharacter.
+fn is_glob_pattern(path: &str) -> bool {
+    path.contains('*') || path.contains('?') || path.contains('[')
+}
+
 impl Validatable for LsRequest {
     fn validate(&self) -> Result<(), ValidationError> {
-        validate_argument(&self.path)?;
-        validate_not_flag(&self.path)?;
-        // Block ".." to keep code simple and prevent any attempts to access blocked paths
-        validate_no_traversal(&self.path)?;
+        let ls_policy = &policy().ls;
+
+        if is_glob_pattern(&self.path) {
+            validate_glob_pattern(&self.path)?;
+        } else {
+            validate_argument(&self.path)?;
+            validate_not_flag(&self.path)?;
+        }
+        if !ls_policy.arg_allowed(&self.path) {
+            return Err(ValidationError::ShellInjection(self.path.clone()));
+        }
+        // Block ".." to keep code simple and prevent any attempts to access blocked paths,
+        // unless the loaded policy explicitly permits traversal for this tool.
+        if !ls_policy.allow_traversal {
+            validate_no_traversal(&self.path)?;
+        }
         validate_path(&self.path)?;
+        validate_path_against(&self.path, &ls_policy.blocked_paths)?;
         Ok(())
     }
 }
 
 /// Execute the ls command with a validated request and execution context
 pub fn execute(req: &LsRequest, ctx: &ExecutionContext) -> String {
-    // Validate that path combined with working_dir doesn't access blocked paths
+    // Validate that path combined with working_dir doesn't access blocked paths, honoring
+    // any `.command-runner-blocklist` files discovered by walking up from working_dir.
     if let Some(ref working_dir) = ctx.working_dir {
-        if let Err(e) = validate_path_with_working_dir(&req.path, working_dir) {
+        if let Err(e) = validate_path_layered(&req.path, working_dir) {
             return format!("Error: {}", e);
         }
     }
 
-    let mut cmd = Command::new("ls");
+    if is_glob_pattern(&req.path) {
+        return execute_glob(&req.path, ctx);
+    }
+
+    let mut cmd = match create_command("ls") {
+        Ok(cmd) => cmd,
+        Err(e) => return format!("Error: {}", e),
+    };
     cmd.args(["-al", &req.path]);
+
+    if req.structured_output {
+        let result = run_command_structured(cmd, ctx);
+        return serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|e| format!("Error: Failed to serialize command result: {}", e));
+    }
     run_command(cmd, ctx).into_string()
 }
 
+/// Expand a glob pattern (resolved against `working_dir` when relative) and
+/// return the matched paths as output lines, one per match.
+fn execute_glob(pattern: &str, ctx: &ExecutionContext) -> String {
+    let resolved = match (&ctx.working_dir, pattern.starts_with('/')) {
+        (Some(dir), false) => format!("{}/{}", dir, pattern),
+        _ => pattern.to_string(),
+    };
+
+    let paths = match glob::glob(&resolved) {
+        Ok(paths) => paths,
+        Err(e) => return format!("Error: Invalid glob pattern: {}", e),
+    };
+
+    let ls_policy = &policy().ls;
+    let mut lines = Vec::new();
+    for entry in paths {
+        let p = match entry {
+            Ok(p) => p,
+            Err(e) => return format!("Error: {}", e),
+        };
+        let matched = p.to_string_lossy().into_owned();
+        // A recursive pattern (e.g. "**/*.pem") can match into a directory the
+        // literal pattern string never named, so every resolved match - not just
+        // the input pattern - has to go through the same path checks as a
+        // non-glob `ls` would.
+        if let Err(e) = validate_path(&matched) {
+            return format!("Error: {}", e);
+        }
+        if let Err(e) = validate_path_against(&matched, &ls_policy.blocked_paths) {
+            return format!("Error: {}", e);
+        }
+        if let Some(ref working_dir) = ctx.working_dir {
+            if let Err(e) = validate_path_layered(&matched, working_dir) {
+                return format!("Error: {}", e);
+            }
+        }
+        lines.push(matched);
+    }
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,6 +157,7 @@ mod tests {
         let temp_dir = setup_test_dir();
         let req = LsRequest {
             path: temp_dir.path().to_string_lossy().to_string(),
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
         let result = execute(&req, &ExecutionContext::default());
@@ -90,6 +171,7 @@ mod tests {
         let temp_dir = setup_test_dir();
         let req = LsRequest {
             path: temp_dir.path().to_string_lossy().to_string(),
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
         let result = execute(&req, &ExecutionContext::default());
@@ -100,6 +182,7 @@ mod tests {
     fn test_ls_tool_nonexistent_path() {
         let req = LsRequest {
             path: "/nonexistent/path".to_string(),
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
         let result = execute(&req, &ExecutionContext::default());
@@ -116,6 +199,7 @@ mod tests {
     fn test_validate_blocks_shell_injection_semicolon() {
         let req = LsRequest {
             path: "/tmp; echo hello".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -127,6 +211,7 @@ mod tests {
     fn test_validate_blocks_shell_injection_pipe() {
         let req = LsRequest {
             path: "/tmp | echo hello".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -138,6 +223,7 @@ mod tests {
     fn test_validate_blocks_shell_injection_backtick() {
         let req = LsRequest {
             path: "`echo hello`".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -149,6 +235,7 @@ mod tests {
     fn test_validate_blocks_shell_injection_dollar() {
         let req = LsRequest {
             path: "$(echo hello)".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -160,6 +247,7 @@ mod tests {
     fn test_validate_allows_other_paths() {
         let req = LsRequest {
             path: "/tmp".to_string(),
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
     }
@@ -168,6 +256,7 @@ mod tests {
     fn test_validate_blocks_flag_injection_single_dash() {
         let req = LsRequest {
             path: "-la".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -179,6 +268,7 @@ mod tests {
     fn test_validate_blocks_flag_injection_double_dash() {
         let req = LsRequest {
             path: "--help".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -190,6 +280,7 @@ mod tests {
     fn test_validate_allows_paths_with_internal_dashes() {
         let req = LsRequest {
             path: "/path/with-dash/file".to_string(),
+            structured_output: false,
         };
         assert!(req.validate().is_ok());
     }
@@ -198,6 +289,7 @@ mod tests {
     fn test_validate_blocks_path_traversal() {
         let req = LsRequest {
             path: "/tmp/../etc".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
@@ -209,10 +301,113 @@ mod tests {
     fn test_validate_blocks_path_traversal_relative() {
         let req = LsRequest {
             path: "../secret".to_string(),
+            structured_output: false,
         };
         assert!(matches!(
             req.validate(),
             Err(ValidationError::PathTraversal(_))
         ));
     }
+
+    // Glob tests
+    #[test]
+    fn test_validate_allows_glob_pattern() {
+        let req = LsRequest {
+            path: "src/**/*.rs".to_string(),
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_glob_still_blocks_shell_injection() {
+        let req = LsRequest {
+            path: "*.rs; echo hello".to_string(),
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::ShellInjection(_))
+        ));
+    }
+
+    #[test]
+    fn test_execute_rejects_path_blocked_by_layered_blocklist_file() {
+        let temp_dir = setup_test_dir();
+        fs::write(temp_dir.path().join(".command-runner-blocklist"), "subdir\n").unwrap();
+
+        let req = LsRequest {
+            path: "subdir".to_string(),
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        assert!(result.starts_with("Error: Reading path"));
+    }
+
+    #[test]
+    fn test_execute_glob_matches_files() {
+        let temp_dir = setup_test_dir();
+        let pattern = format!("{}/*.rs", temp_dir.path().to_string_lossy());
+        let req = LsRequest { path: pattern, structured_output: false };
+        assert!(req.validate().is_ok());
+        let result = execute(&req, &ExecutionContext::default());
+        assert!(result.contains("file2.rs"));
+        assert!(!result.contains("file1.txt"));
+    }
+
+    #[test]
+    fn test_execute_glob_recursive_double_star() {
+        let temp_dir = setup_test_dir();
+        let pattern = format!("{}/**/*.txt", temp_dir.path().to_string_lossy());
+        let req = LsRequest { path: pattern, structured_output: false };
+        assert!(req.validate().is_ok());
+        let result = execute(&req, &ExecutionContext::default());
+        assert!(result.contains("nested.txt"));
+    }
+
+    #[test]
+    fn test_execute_glob_rejects_match_inside_blocked_subdir() {
+        // The literal pattern "**/*.txt" never names "secret" directly, but it can
+        // still recurse into it - each matched path, not just the pattern string,
+        // must be checked against the blocklist.
+        let temp_dir = setup_test_dir();
+        fs::write(temp_dir.path().join(".command-runner-blocklist"), "secret\n").unwrap();
+        fs::create_dir(temp_dir.path().join("secret")).unwrap();
+        let _ = writeln!(
+            File::create(temp_dir.path().join("secret/hidden.txt")).unwrap(),
+            "do not leak"
+        );
+
+        let pattern = format!("{}/**/*.txt", temp_dir.path().to_string_lossy());
+        let req = LsRequest { path: pattern, structured_output: false };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        assert!(result.starts_with("Error: Reading path"));
+    }
+
+    #[test]
+    fn test_execute_structured_output_returns_command_result_json() {
+        let temp_dir = setup_test_dir();
+        let req = LsRequest {
+            path: temp_dir.path().to_string_lossy().to_string(),
+            structured_output: true,
+        };
+        assert!(req.validate().is_ok());
+        let result = execute(&req, &ExecutionContext::default());
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["success"], true);
+        assert_eq!(parsed["exit_code"], 0);
+        assert!(parsed["stdout"].as_str().unwrap().contains("file1.txt"));
+    }
 }
@@ -0,0 +1,7 @@
+pub mod git;
+pub mod ls;
+pub mod test;
+
+pub use git::GitRequest;
+pub use ls::LsRequest;
+pub use test::TestRequest;
@@ -0,0 +1,431 @@
+use regex::Regex;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use crate::executor::{create_command, run_command, run_command_structured};
+use crate::request::ExecutionContext;
+use crate::security::{validate_argument, validate_no_traversal, validate_not_flag, validate_path, validate_path_layered, Validatable, ValidationError};
+
+/// Runners whose `package` is a filesystem path handed straight to the subprocess
+/// (pytest's positional test path, go's package/import path), as opposed to cargo's
+/// `-p`, which names a workspace member rather than a path on disk.
+const PACKAGE_IS_PATH: &[&str] = &["pytest", "go"];
+
+/// Test runners this tool knows how to invoke. Only cargo/libtest and jest-style (npm)
+/// summaries are parsed into counts; for the other runners the counts stay at zero.
+const ALLOWED_RUNNERS: &[&str] = &["cargo", "npm", "deno", "pytest", "go"];
+
+/// Request parameters for the test tool
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TestRequest {
+    /// The test runner to use (cargo, npm, deno, pytest, go). Defaults to "cargo".
+    #[serde(default = "default_runner")]
+    pub runner: String,
+    /// Optional filter/pattern to select a subset of tests
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Optional package/project to scope the run to (e.g. cargo `-p`, go package path)
+    #[serde(default)]
+    pub package: Option<String>,
+    /// Return a structured `CommandResult` (stdout, stderr, exit_code, success) as JSON
+    /// instead of the parsed pass/fail summary. Skips `parse_summary`/`format_summary`
+    /// entirely, so the raw runner output is returned as-is in `stdout`.
+    #[serde(default)]
+    pub structured_output: bool,
+}
+
+fn default_runner() -> String {
+    "cargo".to_string()
+}
+
+impl Validatable for TestRequest {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !ALLOWED_RUNNERS.contains(&self.runner.as_str()) {
+            return Err(ValidationError::DisallowedSubcommand {
+                subcommand: self.runner.clone(),
+                allowed: ALLOWED_RUNNERS.join(", "),
+            });
+        }
+        validate_argument(&self.runner)?;
+        if let Some(filter) = &self.filter {
+            validate_argument(filter)?;
+            validate_not_flag(filter)?;
+        }
+        if let Some(package) = &self.package {
+            validate_argument(package)?;
+            validate_not_flag(package)?;
+            if PACKAGE_IS_PATH.contains(&self.runner.as_str()) {
+                validate_no_traversal(package)?;
+                validate_path(package)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single test failure parsed out of a runner's output
+#[derive(Debug, PartialEq)]
+pub struct TestFailure {
+    pub name: String,
+}
+
+/// Aggregate pass/fail counts parsed from a test runner's output, independent of how
+/// verbose or heterogeneous that runner's terminal formatting is.
+#[derive(Debug, Default, PartialEq)]
+pub struct TestSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    pub failures: Vec<TestFailure>,
+    pub duration_secs: Option<f64>,
+}
+
+/// cargo/libtest: "test result: ok. 4 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.12s"
+static CARGO_RESULT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored; \d+ measured; \d+ filtered out; finished in ([\d.]+)s").unwrap()
+});
+
+/// libtest failure line: "test some::module::test_name ... FAILED"
+static LIBTEST_FAILURE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^test (\S+) \.\.\. FAILED$").unwrap());
+
+/// jest/npm summary line: "Tests:       1 failed, 2 passed, 3 total"
+static JEST_RESULT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Tests:\s+(?:(\d+) failed, )?(?:(\d+) passed, )?(\d+) total").unwrap());
+
+fn build_command(req: &TestRequest) -> Result<Command, String> {
+    let mut cmd = create_command(&req.runner)?;
+    match req.runner.as_str() {
+        "cargo" => {
+            cmd.arg("test");
+            if let Some(package) = &req.package {
+                cmd.args(["-p", package]);
+            }
+            if let Some(filter) = &req.filter {
+                cmd.arg(filter);
+            }
+        }
+        "npm" => {
+            cmd.arg("test");
+            if let Some(filter) = &req.filter {
+                cmd.args(["--", "-t", filter]);
+            }
+        }
+        "deno" => {
+            cmd.arg("test");
+            if let Some(filter) = &req.filter {
+                cmd.args(["--filter", filter]);
+            }
+        }
+        "pytest" => {
+            if let Some(filter) = &req.filter {
+                cmd.args(["-k", filter]);
+            }
+            if let Some(package) = &req.package {
+                cmd.arg(package);
+            }
+        }
+        "go" => {
+            cmd.arg("test");
+            cmd.arg(req.package.as_deref().unwrap_or("./..."));
+            if let Some(filter) = &req.filter {
+                cmd.args(["-run", filter]);
+            }
+        }
+        _ => {}
+    }
+    Ok(cmd)
+}
+
+/// Parse a runner's raw output into a structured pass/fail summary
+fn parse_summary(output: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+
+    if let Some(caps) = CARGO_RESULT_RE.captures(output) {
+        summary.passed += caps[1].parse().unwrap_or(0);
+        summary.failed += caps[2].parse().unwrap_or(0);
+        summary.ignored += caps[3].parse().unwrap_or(0);
+        summary.duration_secs = caps[4].parse().ok();
+    } else if let Some(caps) = JEST_RESULT_RE.captures(output) {
+        summary.failed = caps.get(1).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        summary.passed = caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+    }
+
+    for caps in LIBTEST_FAILURE_RE.captures_iter(output) {
+        summary.failures.push(TestFailure { name: caps[1].to_string() });
+    }
+
+    if summary.total == 0 {
+        summary.total = summary.passed + summary.failed + summary.ignored;
+    }
+
+    summary
+}
+
+/// Format the summary as a stable header, followed by the raw runner output so the
+/// existing transform_output pipeline (grep for a failure, head, etc.) still applies.
+fn format_summary(summary: &TestSummary, raw_output: &str) -> String {
+    let mut lines = vec![format!(
+        "total={} passed={} failed={} ignored={}{}",
+        summary.total,
+        summary.passed,
+        summary.failed,
+        summary.ignored,
+        summary
+            .duration_secs
+            .map(|d| format!(" time={:.2}s", d))
+            .unwrap_or_default(),
+    )];
+    for failure in &summary.failures {
+        lines.push(format!("FAILED: {}", failure.name));
+    }
+    lines.push(String::new());
+    lines.push(raw_output.to_string());
+    lines.join("\n")
+}
+
+/// Execute a test run with a validated request and execution context
+pub fn execute(req: &TestRequest, ctx: &ExecutionContext) -> String {
+    // `validate()` has no access to `working_dir`, so a package path that's safe
+    // against the process's own cwd could still resolve into a blocked location once
+    // combined with the actual working_dir this request runs in - recheck here the way
+    // `ls::execute`/`git::execute` do for their own path-shaped fields.
+    if let (Some(package), Some(working_dir)) = (&req.package, &ctx.working_dir) {
+        if PACKAGE_IS_PATH.contains(&req.runner.as_str()) {
+            if let Err(e) = validate_path_layered(package, working_dir) {
+                return format!("Error: {}", e);
+            }
+        }
+    }
+
+    let cmd = match build_command(req) {
+        Ok(cmd) => cmd,
+        Err(e) => return format!("Error: {}", e),
+    };
+
+    if req.structured_output {
+        let result = run_command_structured(cmd, ctx);
+        return serde_json::to_string_pretty(&result)
+            .unwrap_or_else(|e| format!("Error: Failed to serialize command result: {}", e));
+    }
+
+    let raw_output = run_command(cmd, ctx).into_string();
+    let summary = parse_summary(&raw_output);
+    format_summary(&summary, &raw_output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_disallowed_runner() {
+        let req = TestRequest {
+            runner: "make".to_string(),
+            filter: None,
+            package: None,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::DisallowedSubcommand { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_shell_injection_in_filter() {
+        let req = TestRequest {
+            runner: "cargo".to_string(),
+            filter: Some("foo; rm -rf /".to_string()),
+            package: None,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::ShellInjection(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_flag_like_filter() {
+        // `filter` is appended as a bare positional arg for several runners (e.g. cargo),
+        // so a value starting with `-` could be smuggled in as a flag instead.
+        let req = TestRequest {
+            runner: "cargo".to_string(),
+            filter: Some("--exact".to_string()),
+            package: None,
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::FlagInjection(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_flag_like_package() {
+        let req = TestRequest {
+            runner: "go".to_string(),
+            filter: None,
+            package: Some("--bad".to_string()),
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::FlagInjection(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_traversal_package_for_pytest() {
+        let req = TestRequest {
+            runner: "pytest".to_string(),
+            filter: None,
+            package: Some("../../etc/passwd".to_string()),
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_traversal_package_for_go() {
+        let req = TestRequest {
+            runner: "go".to_string(),
+            filter: None,
+            package: Some("../../etc".to_string()),
+            structured_output: false,
+        };
+        assert!(matches!(
+            req.validate(),
+            Err(ValidationError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_does_not_path_check_cargo_package() {
+        // cargo's `package` is a workspace member name, not a filesystem path, so it
+        // must not be run through path validation the way pytest/go's are.
+        let req = TestRequest {
+            runner: "cargo".to_string(),
+            filter: None,
+            package: Some("../sibling-crate".to_string()),
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_execute_rejects_pytest_package_blocked_via_working_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".command-runner-blocklist"), "secret\n").unwrap();
+        std::fs::create_dir(temp_dir.path().join("secret")).unwrap();
+
+        let req = TestRequest {
+            runner: "pytest".to_string(),
+            filter: None,
+            package: Some("secret".to_string()),
+            structured_output: false,
+        };
+        assert!(req.validate().is_ok());
+
+        let ctx = ExecutionContext {
+            working_dir: Some(temp_dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = execute(&req, &ctx);
+        assert!(result.starts_with("Error: Reading path"));
+    }
+
+    #[test]
+    fn test_validate_allows_default_runner() {
+        let req: TestRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(req.runner, "cargo");
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_cargo_summary_all_passing() {
+        let output = "running 4 tests\ntest it_works ... ok\n\ntest result: ok. 4 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.12s\n";
+        let summary = parse_summary(output);
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.passed, 4);
+        assert_eq!(summary.failed, 0);
+        assert_eq!(summary.duration_secs, Some(0.12));
+        assert!(summary.failures.is_empty());
+    }
+
+    #[test]
+    fn test_parse_cargo_summary_with_failures() {
+        let output = "test foo::bar ... FAILED\ntest foo::baz ... ok\n\nfailures:\n    foo::bar\n\ntest result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.05s\n";
+        let summary = parse_summary(output);
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.failures, vec![TestFailure { name: "foo::bar".to_string() }]);
+    }
+
+    #[test]
+    fn test_parse_jest_summary() {
+        let output = "Tests:       1 failed, 2 passed, 3 total\n";
+        let summary = parse_summary(output);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.total, 3);
+    }
+
+    #[test]
+    fn test_parse_unrecognized_output_yields_zeroed_summary() {
+        let summary = parse_summary("some unrelated output\n");
+        assert_eq!(summary, TestSummary::default());
+    }
+
+    #[test]
+    fn test_format_summary_includes_header_and_raw_output() {
+        let summary = TestSummary {
+            total: 2,
+            passed: 1,
+            failed: 1,
+            ignored: 0,
+            failures: vec![TestFailure { name: "foo::bar".to_string() }],
+            duration_secs: Some(0.05),
+        };
+        let formatted = format_summary(&summary, "raw runner output");
+        assert!(formatted.starts_with("total=2 passed=1 failed=1 ignored=0 time=0.05s"));
+        assert!(formatted.contains("FAILED: foo::bar"));
+        assert!(formatted.contains("raw runner output"));
+    }
+
+    #[test]
+    fn test_build_command_cargo_with_filter_and_package() {
+        let req = TestRequest {
+            runner: "cargo".to_string(),
+            filter: Some("my_test".to_string()),
+            package: Some("my-crate".to_string()),
+            structured_output: false,
+        };
+        let cmd = build_command(&req).expect("cargo should be resolvable on PATH");
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(args, vec!["test", "-p", "my-crate", "my_test"]);
+    }
+
+    #[test]
+    fn test_execute_structured_output_returns_command_result_json() {
+        let req = TestRequest {
+            runner: "cargo".to_string(),
+            filter: None,
+            package: None,
+            structured_output: true,
+        };
+        let result = execute(&req, &ExecutionContext::default());
+        let parsed: serde_json::Value = serde_json::from_str(&result)
+            .unwrap_or_else(|e| panic!("expected JSON, got {}: {}", result, e));
+        assert!(parsed.get("success").is_some());
+        assert!(parsed.get("exit_code").is_some());
+    }
+}
@@ -0,0 +1,78 @@
+use std::env;
+
+/// Transport selection for serving the MCP server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// Communicate over stdin/stdout with a single local client (the default).
+    Stdio,
+    /// Serve over HTTP using Server-Sent Events, so multiple agents can connect concurrently.
+    Sse { bind: String },
+}
+
+impl Transport {
+    const DEFAULT_BIND: &'static str = "127.0.0.1:8080";
+
+    /// Resolve the transport to use from CLI args (`--transport stdio|sse --bind ADDR`),
+    /// falling back to the `MCP_TRANSPORT`/`MCP_BIND` environment variables, and finally
+    /// to stdio for backward compatibility.
+    pub fn from_env_and_args(args: &[String]) -> Self {
+        let transport = Self::arg_value(args, "--transport")
+            .or_else(|| env::var("MCP_TRANSPORT").ok())
+            .unwrap_or_else(|| "stdio".to_string());
+
+        let bind = Self::arg_value(args, "--bind")
+            .or_else(|| env::var("MCP_BIND").ok())
+            .unwrap_or_else(|| Self::DEFAULT_BIND.to_string());
+
+        match transport.as_str() {
+            "sse" => Transport::Sse { bind },
+            _ => Transport::Stdio,
+        }
+    }
+
+    fn arg_value(args: &[String], flag: &str) -> Option<String> {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_to_stdio() {
+        assert_eq!(Transport::from_env_and_args(&[]), Transport::Stdio);
+    }
+
+    #[test]
+    fn test_parses_sse_flag() {
+        let args = vec!["--transport".to_string(), "sse".to_string()];
+        assert_eq!(
+            Transport::from_env_and_args(&args),
+            Transport::Sse { bind: Transport::DEFAULT_BIND.to_string() }
+        );
+    }
+
+    #[test]
+    fn test_parses_bind_flag() {
+        let args = vec![
+            "--transport".to_string(),
+            "sse".to_string(),
+            "--bind".to_string(),
+            "0.0.0.0:9000".to_string(),
+        ];
+        assert_eq!(
+            Transport::from_env_and_args(&args),
+            Transport::Sse { bind: "0.0.0.0:9000".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_unknown_transport_falls_back_to_stdio() {
+        let args = vec!["--transport".to_string(), "bogus".to_string()];
+        assert_eq!(Transport::from_env_and_args(&args), Transport::Stdio);
+    }
+}
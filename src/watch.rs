@@ -0,0 +1,102 @@
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+
+/// Safety backstop so a watch session can't loop forever even if the caller never
+/// disconnects or the filesystem never goes quiet.
+const MAX_WATCH_ITERATIONS: usize = 1000;
+
+/// Watch `paths` recursively for filesystem changes and invoke `on_change` after each
+/// debounced batch of events, until `on_change` returns `false`, the iteration cap is
+/// hit, or `deadline` elapses. `on_change` receives the 1-based iteration number.
+pub fn watch_and_rerun<F>(paths: &[String], debounce: Duration, deadline: Option<Duration>, mut on_change: F)
+where
+    F: FnMut(usize) -> bool,
+{
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    }) {
+        Ok(w) => w,
+        Err(_) => return,
+    };
+
+    for path in paths {
+        let _ = watcher.watch(Path::new(path), RecursiveMode::Recursive);
+    }
+
+    let start = Instant::now();
+    let mut iteration = 0;
+    let poll_interval = debounce.max(Duration::from_millis(1));
+
+    loop {
+        if iteration >= MAX_WATCH_ITERATIONS {
+            break;
+        }
+        if let Some(deadline) = deadline {
+            if start.elapsed() >= deadline {
+                break;
+            }
+        }
+
+        match rx.recv_timeout(poll_interval) {
+            Ok(_) => {
+                // Drain any further events that arrive within the debounce window so a
+                // burst of writes collapses into a single re-run.
+                while rx.recv_timeout(debounce).is_ok() {}
+                iteration += 1;
+                if !on_change(iteration) {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_watch_and_rerun_fires_on_file_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        let watched = Arc::new(Mutex::new(0usize));
+        let watched_writer = watched.clone();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            fs::write(temp_dir.path().join("touched.txt"), b"hello").unwrap();
+            let _ = watched_writer;
+        });
+
+        watch_and_rerun(&[path], Duration::from_millis(50), Some(Duration::from_secs(5)), |iteration| {
+            *watched.lock().unwrap() = iteration;
+            false
+        });
+
+        assert_eq!(*watched.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_watch_and_rerun_respects_deadline_with_no_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        let mut calls = 0;
+
+        watch_and_rerun(&[path], Duration::from_millis(20), Some(Duration::from_millis(100)), |_| {
+            calls += 1;
+            true
+        });
+
+        assert_eq!(calls, 0);
+    }
+}